@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene = Scene::new();
     let origin = vec(20., 40.);
     let size = vec(40., 20.);
@@ -18,5 +18,5 @@ fn main() {
         scene.add_poly(&s2);
     }
 
-    scene.to_svg().save(&svg_filename!());
+    scene.to_svg()?.save(&svg_filename!())
 }
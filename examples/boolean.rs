@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+fn star(center: Point, points: usize, inner: f64, outer: f64) -> Polygon {
+    Polygon::new(
+        (0..2 * points)
+            .map(|i| {
+                let r = if i % 2 == 0 { outer } else { inner };
+                center + r * Vector::from_angle(i as f64 * PI / points as f64)
+            })
+            .collect(),
+    )
+}
+
+fn blob(center: Point, steps: usize) -> Polygon {
+    Polygon::new(
+        (0..steps)
+            .map(|i| {
+                let t = i as f64 / steps as f64;
+                center + (20. + 5. * (TWO_PI * 3. * t).sin()) * Vector::from_angle(t * TWO_PI)
+            })
+            .collect(),
+    )
+}
+
+fn main() -> Result<(), Error> {
+    let mut scene = Scene::new();
+
+    // A circle with a star punched out of it.
+    let disk = circle(100).scale(30.).translate(vec(-40., 0.));
+    for poly in disk.difference(&star(pt(-40., 0.), 5, 10., 25.)) {
+        scene.add_poly_with_draw_mode(
+            &poly,
+            pen(0).hatch(
+                Hatch::Parallel {
+                    angle: PI / 4.,
+                    spacing: 2.,
+                },
+                1,
+            ),
+        );
+    }
+
+    // The union of overlapping blobs.
+    let mut blobs = vec![blob(pt(25., -10.), 120)];
+    for center in &[pt(45., 5.), pt(30., 15.)] {
+        blobs = blobs
+            .iter()
+            .flat_map(|b| b.union(&blob(*center, 120)))
+            .collect();
+    }
+    for poly in &blobs {
+        scene.add_poly(poly);
+    }
+
+    // The parts covered by exactly one of a star and a blob.
+    let center = pt(0., -60.);
+    for poly in star(center, 7, 12., 30.).xor(&blob(center, 120)) {
+        scene.add_poly_with_draw_mode(&poly, pen(2).hatch(Hatch::Contour { spacing: 1.5 }, 2));
+    }
+
+    scene.to_svg()?.save(&svg_filename!())
+}
@@ -0,0 +1,29 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+fn main() -> Result<(), Error> {
+    let mut scene3d = Scene3::new();
+
+    // A field of pillars that extends behind the camera, which sits among
+    // them near the ground.
+    for i in -4_i32..=4 {
+        for j in -3..8 {
+            let pillar = cube()
+                .scale3(vec3(1., 1., 2. + (i * 7 + j * 3).rem_euclid(4) as f64))
+                .translate(vec3(4. * i as f64, 4. * j as f64, 0.));
+            scene3d.add_form(pillar);
+        }
+    }
+
+    let camera = Camera::new(pt3(2., -6., 2.5), pt3(0., 20., 0.))
+        .fov(PI / 2.5)
+        .aspect(1.4);
+
+    scene3d
+        .camera(camera)
+        .depth_sort(DepthSort::Bsp)
+        .to_2d()
+        .to_svg()?
+        .save(&svg_filename!())
+}
@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene = Scene::new();
     let origin = pt(0., 0.);
     let steps = 200;
@@ -20,5 +20,5 @@ fn main() {
     scene.add_poly(&p1);
     scene.add_poly(&circle(100).scale(100.));
 
-    scene.to_svg().save(&svg_filename!());
+    scene.to_svg()?.save(&svg_filename!())
 }
@@ -9,12 +9,12 @@ fn draw_cone(scene: &mut Scene, place: Vector, scale: f64) {
 
     for _ in 1..50 {
         scene.add_poly(&circle(100).scale2(size).translate(c));
-        size = size * 0.94;
-        c = c + dir;
+        size *= 0.94;
+        c += dir;
     }
 }
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene = scenelib::scene::Scene::new();
     let offset = 320.;
 
@@ -27,5 +27,5 @@ fn main() {
         }
     }
 
-    scene.to_svg().save(&svg_filename!());
+    scene.to_svg()?.save(&svg_filename!())
 }
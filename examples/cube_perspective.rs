@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene3d = Scene3::new();
 
     for i in 0..10 {
@@ -14,11 +14,18 @@ fn main() {
         }
     }
 
-    let mut plot = scene3d.camera_distance(100.).to_2d().to_plot();
+    let mut plot = scene3d.camera_distance(100.).to_2d().to_plot()?;
 
     println!("Before optimization: {:?}", plot.cost());
     plot = plot.optimize();
     println!("After optimization: {:?}", plot.cost());
 
-    plot.to_svg().save(&svg_filename!());
+    let joined = plot.clone().join_paths(1e-6);
+    println!(
+        "Savings from joining paths: {:?}",
+        plot.cost() - joined.cost()
+    );
+    plot = joined;
+
+    plot.to_svg().save(&svg_filename!())
 }
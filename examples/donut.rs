@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene3d: Scene3 = Scene3::new();
     let circles = 360;
     let divisions = 120;
@@ -34,16 +34,14 @@ fn main() {
             .scale(10.)
             .translate(vec3(-200., 0., 0.))
             .rotate_euler(0., theta, 0.);
-        scene3d.add_poly_with_pen(
-            p3,
-            pen(if pseudo_random(y as f64) > 0.4 {
-                1
-            } else {
-                0
-            }),
-        );
+        scene3d.add_poly_with_pen(p3, pen(if pseudo_random(y as f64) > 0.4 { 1 } else { 0 }));
     }
 
     let scene = scene3d.camera_distance(10000.).to_2d();
-    scene.to_svg().axidraw_portrait().save(&svg_filename!());
+    let greedy = scene.to_plot()?.optimize();
+    let plot = scene.to_plot()?.optimize_with(Strategy::TwoOpt {
+        budget: Budget::Iterations(20),
+    });
+    println!("Savings from 2-opt: {:?}", greedy.cost() - plot.cost());
+    plot.to_svg().axidraw_portrait().save(&svg_filename!())
 }
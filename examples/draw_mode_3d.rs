@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene3d: Scene3 = Scene3::new();
 
     scene3d.add_form(cube().draw_mode(pen(0)));
@@ -12,5 +12,5 @@ fn main() {
     scene3d.add_form(cube().translate(vec3(0., 1.1, 0.)).draw_mode(pen(2)));
 
     let scene = scene3d.to_2d();
-    scene.to_svg().save(&svg_filename!());
+    scene.to_svg()?.save(&svg_filename!())
 }
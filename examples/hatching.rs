@@ -0,0 +1,51 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+fn flower(center: Point, steps: usize) -> Polygon {
+    Polygon::new(
+        (0..steps)
+            .map(|i| {
+                let t = i as f64 / steps as f64;
+                center + (30. + 12. * (PI * 10. * t).sin()) * Vector::from_angle(t * TWO_PI)
+            })
+            .collect(),
+    )
+}
+
+fn main() -> Result<(), Error> {
+    let mut scene = Scene::new();
+
+    let ring = Polygon::with_holes(
+        circle(80).scale(40.).points.0,
+        vec![circle(80).scale(20.).points.reversed().0],
+    );
+    scene.add_poly_with_draw_mode(
+        &ring,
+        pen(0).hatch(
+            Hatch::Parallel {
+                angle: PI / 4.,
+                spacing: 2.,
+            },
+            1,
+        ),
+    );
+
+    scene.add_poly_with_draw_mode(
+        &flower(pt(45., 10.), 200),
+        pen(0).hatch(Hatch::Contour { spacing: 3. }, 2),
+    );
+
+    scene.add_poly_with_draw_mode(
+        &square().scale(15.).translate(vec(20., -40.)),
+        pen(0).hatch(
+            Hatch::Cross {
+                angle: 0.3,
+                spacing: 2.5,
+            },
+            0,
+        ),
+    );
+
+    scene.to_svg()?.save(&svg_filename!())
+}
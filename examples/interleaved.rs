@@ -0,0 +1,19 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+fn main() -> Result<(), Error> {
+    let mut scene3d = Scene3::new().depth_sort(DepthSort::Bsp);
+
+    // Three slabs that pass through each other, which cannot be drawn
+    // correctly by sorting whole polygons.
+    for i in 0..3 {
+        let slab = cube()
+            .scale3(vec3(4., 1., 0.2))
+            .rotate_euler(0.45, 0.25, i as f64 * TWO_PI / 3.)
+            .draw_mode(pen(i));
+        scene3d.add_form(slab);
+    }
+
+    scene3d.to_2d().to_svg()?.save(&svg_filename!())
+}
@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene = Scene::new();
 
     let s1 = square();
@@ -10,5 +10,9 @@ fn main() {
     scene.add_poly_with_draw_mode(&s1, pen(1));
     scene.add_poly_with_draw_mode(&s2, pen(2));
 
-    scene.to_svg().save(&svg_filename!());
+    scene
+        .to_svg()?
+        .pen(1, Pen::new("darkorange", 0.5).named("Orange marker"))
+        .pen(2, Pen::new("teal", 0.8).named("Teal brush"))
+        .save(&svg_filename!())
 }
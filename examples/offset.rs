@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+fn star(center: Point, points: usize, inner: f64, outer: f64) -> Polygon {
+    Polygon::new(
+        (0..2 * points)
+            .map(|i| {
+                let r = if i % 2 == 0 { outer } else { inner };
+                center + r * Vector::from_angle(i as f64 * PI / points as f64)
+            })
+            .collect(),
+    )
+}
+
+fn main() -> Result<(), Error> {
+    let mut scene = Scene::new();
+
+    // Borders around a star with each join style, and an inset inside it.
+    let joins = [Join::Miter { limit: 4. }, Join::Round, Join::Bevel];
+    for (i, join) in joins.iter().enumerate() {
+        let shape = star(pt(70. * i as f64, 0.), 5, 12., 30.);
+        scene.add_poly_with_draw_mode(&shape, stroke(0));
+        for k in 1..4 {
+            for poly in shape.offset(2. * k as f64, *join) {
+                scene.add_poly_with_draw_mode(&poly, stroke(1));
+            }
+        }
+        for poly in shape.offset(-3., *join) {
+            scene.add_poly_with_draw_mode(&poly, stroke(2));
+        }
+    }
+
+    // A ring grown until its hole closes up.
+    let ring = Polygon::with_holes(
+        circle(60).scale(25.).translate(vec(70., -80.)).points.0,
+        vec![star(pt(70., -80.), 4, 6., 15.).points.0],
+    );
+    scene.add_poly_with_draw_mode(&ring, stroke(0));
+    for k in 1..6 {
+        for poly in ring.offset(1.5 * k as f64, Join::Round) {
+            scene.add_poly_with_draw_mode(&poly, stroke(1));
+        }
+    }
+
+    scene.to_svg()?.save(&svg_filename!())
+}
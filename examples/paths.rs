@@ -0,0 +1,75 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+/// A leaf made of two cubic curves.
+fn leaf(path: &mut PathBuilder, base: Point, tip: Point, width: f64) {
+    let along = tip - base;
+    let across = vec(-along.y, along.x).normalize() * width;
+    path.move_to(base)
+        .cubic_to(
+            base + along * 0.3 + across,
+            tip - along * 0.2 + across * 0.5,
+            tip,
+        )
+        .cubic_to(
+            tip - along * 0.2 - across * 0.5,
+            base + along * 0.3 - across,
+            base,
+        )
+        .close();
+}
+
+fn main() -> Result<(), Error> {
+    let mut scene = Scene::new();
+
+    // A flower with leaves on a curved stem.
+    let mut stem = PathBuilder::new();
+    stem.move_to(pt(0., 60.)).quad_to(pt(20., 20.), pt(0., 0.));
+    for polyline in stem.polylines() {
+        scene.add_polyline(&polyline, 2);
+    }
+
+    let mut leaves = PathBuilder::new();
+    leaf(&mut leaves, pt(8., 35.), pt(30., 30.), 6.);
+    leaf(&mut leaves, pt(8., 45.), pt(-15., 50.), 5.);
+    for poly in leaves.polygons() {
+        scene.add_poly_with_draw_mode(
+            &poly,
+            pen(2).hatch(
+                Hatch::Parallel {
+                    angle: PI / 3.,
+                    spacing: 1.5,
+                },
+                2,
+            ),
+        );
+    }
+
+    // Petals drawn with arcs around a round center.
+    let mut petals = PathBuilder::new();
+    for i in 0..8 {
+        let angle = i as f64 * TWO_PI / 8.;
+        let a = pt(0., 0.) + 6. * Vector::from_angle(angle - 0.3);
+        let b = pt(0., 0.) + 6. * Vector::from_angle(angle + 0.3);
+        petals
+            .move_to(a)
+            .arc_to(vec(10., 4.), angle, true, true, b)
+            .close();
+    }
+    for poly in petals.polygons() {
+        scene.add_poly_with_draw_mode(&poly, pen(1));
+    }
+
+    let mut center = PathBuilder::new();
+    center
+        .move_to(pt(-6., 0.))
+        .arc_to(vec(6., 6.), 0., false, true, pt(6., 0.))
+        .arc_to(vec(6., 6.), 0., false, true, pt(-6., 0.))
+        .close();
+    for poly in center.polygons() {
+        scene.add_poly_with_draw_mode(&poly, pen(0).hatch(Hatch::Contour { spacing: 1. }, 0));
+    }
+
+    scene.to_svg()?.save(&svg_filename!())
+}
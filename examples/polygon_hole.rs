@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene = Scene::new();
 
     for i in 5..30 {
@@ -18,5 +18,5 @@ fn main() {
     );
     scene.add_poly(&poly);
 
-    scene.to_svg().save(&svg_filename!());
+    scene.to_svg()?.save(&svg_filename!())
 }
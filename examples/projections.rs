@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+/// An L-shaped block with a notch, so that each view looks different.
+fn block() -> Scene3 {
+    let mut scene3d = Scene3::new();
+    scene3d.add_form(cube().scale3(vec3(3., 2., 1.)));
+    scene3d.add_form(
+        cube()
+            .scale3(vec3(1., 2., 2.))
+            .translate(vec3(-1., 0., 1.5)),
+    );
+    scene3d.add_form(cube().scale(0.8).translate(vec3(1., 0., 0.9)));
+    scene3d
+}
+
+fn main() -> Result<(), Error> {
+    let projections = vec![
+        Projection::Isometric,
+        Projection::Dimetric { elevation: 0.25 },
+        Projection::Trimetric {
+            azimuth: 0.4,
+            elevation: 0.5,
+        },
+        Projection::Cavalier { angle: PI / 4. },
+        Projection::Cabinet { angle: PI / 6. },
+        Projection::Top,
+        Projection::Front,
+        Projection::Side,
+    ];
+
+    let mut scene = Scene::new();
+    for (i, projection) in projections.into_iter().enumerate() {
+        let offset = vec(6. * (i % 4) as f64, 6. * (i / 4) as f64);
+        let view = block()
+            .projection(projection)
+            .depth_sort(DepthSort::Bsp)
+            .to_2d();
+        for layer in view.to_plot()?.layers {
+            for line in layer.lines {
+                scene.add_segment(line.translate(offset));
+            }
+        }
+    }
+
+    scene.to_svg()?.save(&svg_filename!())
+}
@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene = Scene::new();
 
     scene.add_segment(LineSegment::new(
@@ -21,5 +21,5 @@ fn main() {
         scene.add_poly(&poly);
     }
 
-    scene.to_svg().save(&svg_filename!());
+    scene.to_svg()?.save(&svg_filename!())
 }
@@ -0,0 +1,26 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+fn main() -> Result<(), Error> {
+    let mut scene3d = Scene3::new()
+        .light(Light::Directional(vec3(-1., 2., 3.)))
+        .shading(Shading {
+            min_spacing: 0.4,
+            max_spacing: 3.,
+            ..Default::default()
+        });
+
+    for i in 0..4 {
+        let cube = cube()
+            .scale(10.)
+            .rotate_euler(0., 0., i as f64 * PI / 8.)
+            .translate(vec3(16. * i as f64, 0., 0.));
+        scene3d.add_form(cube);
+    }
+
+    let cylinder = Form::extrude_from_poly(&circle(24).scale(6.), 6.).translate(vec3(-20., 0., 0.));
+    scene3d.add_form(cylinder);
+
+    scene3d.to_2d().to_svg()?.save(&svg_filename!())
+}
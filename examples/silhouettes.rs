@@ -0,0 +1,30 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+fn main() -> Result<(), Error> {
+    // Smooth shapes keep only their outlines, while the edges of the
+    // polyhedra are sharp enough to be drawn as creases.
+    let mut scene3d = Scene3::new()
+        .depth_sort(DepthSort::Bsp)
+        .back_face_culling(true)
+        .edges(Edges::Silhouette {
+            crease_angle: PI / 6.,
+        });
+
+    let forms = vec![
+        cube().scale(1.5),
+        dodecahedron(),
+        sphere(24, 12),
+        cylinder(24).scale3(vec3(0.8, 0.8, 1.)),
+        torus(0.35, 32, 16).scale(0.8),
+        capsule(0.6, 24, 6).scale(0.6),
+    ];
+
+    for (i, form) in forms.into_iter().enumerate() {
+        let position = vec3(3. * (i % 3) as f64, -3. * (i / 3) as f64, 0.);
+        scene3d.add_form(form.translate(position));
+    }
+
+    scene3d.to_2d().to_svg()?.save(&svg_filename!())
+}
@@ -0,0 +1,30 @@
+#[macro_use]
+extern crate scenelib;
+use scenelib::prelude::*;
+
+fn main() -> Result<(), Error> {
+    let mut scene3d = Scene3::new()
+        .depth_sort(DepthSort::Bsp)
+        .light(Light::Directional(vec3(-1., -2., 3.)));
+
+    let forms = vec![
+        tetrahedron(),
+        cube().scale(1.5),
+        octahedron(),
+        dodecahedron(),
+        icosahedron(),
+        sphere(16, 8),
+        icosphere(2),
+        cylinder(16).scale3(vec3(0.8, 0.8, 1.)),
+        cone(16).scale3(vec3(0.8, 0.8, 1.)),
+        torus(0.35, 24, 10).scale(0.8),
+        capsule(0.6, 16, 4).scale(0.6),
+    ];
+
+    for (i, form) in forms.into_iter().enumerate() {
+        let position = vec3(3. * (i % 4) as f64, -3. * (i / 4) as f64, 0.);
+        scene3d.add_form(form.translate(position));
+    }
+
+    scene3d.to_2d().to_svg()?.save(&svg_filename!())
+}
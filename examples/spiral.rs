@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene = Scene::new();
     let origin = pt(0., 0.);
 
@@ -10,7 +10,7 @@ fn main() {
 
     for i in 0..100 {
         let y = i as f64 * 3. - 200.;
-        scene.add_segment(LineSegment::new(pt(-300., y), pt(300., y + 20.)))
+        scene.add_segment(LineSegment::new(pt(-300., y), pt(300., y + 20.)));
     }
 
     let mut sp1: Vec<Point> = Vec::new();
@@ -18,7 +18,7 @@ fn main() {
 
     for i in 10..400 {
         let angle = (i as f64 * (TWO_PI / 5.)).sqrt();
-        pos = pos + Vector::from_angle(angle) * 10.;
+        pos += Vector::from_angle(angle) * 10.;
         let p1 = pos + Vector::from_angle(angle + PI / 4.) * 20.;
         let p2 = pos + Vector::from_angle(angle - PI / 4.) * 20.;
 
@@ -30,5 +30,5 @@ fn main() {
     sp1.append(&mut sp2);
     scene.add_poly(&Polygon::new(sp1));
 
-    scene.to_svg().save(&svg_filename!());
+    scene.to_svg()?.save(&svg_filename!())
 }
@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene3d: Scene3 = Scene3::new();
 
     for i in 0..50 {
@@ -17,5 +17,5 @@ fn main() {
     }
 
     let scene = scene3d.to_2d();
-    scene.to_svg().save(&svg_filename!());
+    scene.to_svg()?.save(&svg_filename!())
 }
@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene3d: Scene3 = Scene3::new();
 
     const LAT_MIN: f64 = PI / 4.;
@@ -26,5 +26,5 @@ fn main() {
         }
     }
 
-    scene3d.to_2d().to_svg().save(&svg_filename!());
+    scene3d.to_2d().to_svg()?.save(&svg_filename!())
 }
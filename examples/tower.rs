@@ -2,7 +2,7 @@
 extern crate scenelib;
 use scenelib::prelude::*;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mut scene3d: Scene3 = Scene3::new();
     let control_points = 20;
     let resolution = 20;
@@ -31,5 +31,5 @@ fn main() {
     }
 
     let scene = scene3d.camera_distance(8000.).to_2d();
-    scene.to_svg().axidraw_portrait().save(&svg_filename!());
+    scene.to_svg()?.axidraw_portrait().save(&svg_filename!())
 }
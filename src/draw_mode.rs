@@ -1,12 +1,55 @@
 //! Specifies how a polygon is added to a scene.
 
+use crate::geom::hatch::Hatch;
+
 /// Represents a mode for drawing a polygon to a plot.
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct DrawMode {
     /// The pen to use to outline the polygon. If not given, no outline is drawn.
     pub pen: Option<usize>,
     /// Whether to simulate fill, by erasing lines that the polygon overlaps.
     pub fill: bool,
+    /// A pattern to hatch the inside of the polygon with, and the pen to use.
+    pub hatch: Option<(Hatch, usize)>,
+    /// The pens whose lines are erased by the fill.
+    pub occludes: PenMask,
+}
+
+impl DrawMode {
+    /// Hatch the inside of the polygon with the given pattern and pen.
+    pub fn hatch(mut self, hatch: Hatch, pen: usize) -> DrawMode {
+        self.hatch = Some((hatch, pen));
+        self
+    }
+
+    /// Only erase lines drawn with the given pens when filling.
+    pub fn occluding(mut self, occludes: PenMask) -> DrawMode {
+        self.occludes = occludes;
+        self
+    }
+}
+
+/// A set of pens, used to choose which lines a fill erases.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum PenMask {
+    /// Every pen.
+    #[default]
+    All,
+    /// Only the given pens.
+    Only(Vec<usize>),
+    /// Every pen except the given ones.
+    Except(Vec<usize>),
+}
+
+impl PenMask {
+    /// Whether the set includes the given pen.
+    pub fn contains(&self, pen: usize) -> bool {
+        match self {
+            PenMask::All => true,
+            PenMask::Only(pens) => pens.contains(&pen),
+            PenMask::Except(pens) => !pens.contains(&pen),
+        }
+    }
 }
 
 impl std::default::Default for DrawMode {
@@ -14,6 +57,8 @@ impl std::default::Default for DrawMode {
         DrawMode {
             pen: Some(0),
             fill: true,
+            hatch: None,
+            occludes: PenMask::All,
         }
     }
 }
@@ -24,6 +69,8 @@ pub fn stroke(pen: usize) -> DrawMode {
     DrawMode {
         pen: Some(pen),
         fill: false,
+        hatch: None,
+        occludes: PenMask::All,
     }
 }
 
@@ -32,6 +79,8 @@ pub fn fill_only() -> DrawMode {
     DrawMode {
         pen: None,
         fill: true,
+        hatch: None,
+        occludes: PenMask::All,
     }
 }
 
@@ -40,5 +89,7 @@ pub fn pen(pen: usize) -> DrawMode {
     DrawMode {
         pen: Some(pen),
         fill: true,
+        hatch: None,
+        occludes: PenMask::All,
     }
 }
@@ -0,0 +1,53 @@
+//! Errors that can occur when importing, converting and exporting scenes.
+
+use std::fmt;
+use std::io;
+
+/// An error from importing, converting or exporting a scene.
+#[derive(Debug)]
+pub enum Error {
+    /// Reading or writing a file failed.
+    Io(io::Error),
+    /// An imported file is malformed. The message says what is wrong with
+    /// it, and where.
+    Parse(String),
+    /// The scene has no lines and no frame, so there is nothing to place on
+    /// the page.
+    EmptyScene,
+    /// The plot's bounds have no width and no height (or are not finite), so
+    /// they cannot be scaled to fit the page.
+    ZeroSize,
+    /// The margins are as wide or as tall as the paper, leaving no space to
+    /// draw in.
+    MarginsTooLarge,
+}
+
+/// A result whose error is an `Error`.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+            Error::Parse(message) => write!(f, "invalid file: {}", message),
+            Error::EmptyScene => write!(f, "the scene is empty"),
+            Error::ZeroSize => write!(f, "the plot has zero size"),
+            Error::MarginsTooLarge => write!(f, "the margins leave no space on the page"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
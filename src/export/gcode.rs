@@ -0,0 +1,297 @@
+//! G-code output for GRBL-style pen plotters.
+
+use crate::error::Result;
+use crate::export::page::{Align, Layout, Margins, PageTransform, Paper, Scale};
+use crate::plot::Plot;
+use crate::scene::Scene;
+use crate::types::Point;
+use std::fmt::Write;
+
+/// How the plotter raises and lowers the pen.
+#[derive(Clone, Copy, Debug)]
+pub enum PenControl {
+    /// Drive a servo with `M3 S<value>`, as used by the common servo builds of GRBL.
+    Servo { up: f64, down: f64 },
+    /// Move the Z axis to the given heights in mm.
+    ZAxis { up: f64, down: f64 },
+}
+
+/// Builder for writing a `Plot` to a G-code file.
+///
+/// Paper coordinates are in mm with the origin at the bottom left of the
+/// page, so that the output has the same orientation as the SVG preview.
+/// The plot is placed on the page in the same way as by `SVGWriter`.
+pub struct GCodeWriter {
+    /// The plot to write.
+    plot: Plot,
+    /// Commands used to raise and lower the pen.
+    pen_control: PenControl,
+    /// Feed rate in mm/min while the pen is down.
+    draw_feed: f64,
+    /// Feed rate in mm/min while the pen is up.
+    travel_feed: f64,
+    /// Time in seconds to wait after raising or lowering the pen.
+    pen_delay: f64,
+    /// Placement of the plot on the page.
+    layout: Layout,
+}
+
+/// Objects that can be turned into a G-code builder.
+pub trait WriteGCode {
+    /// The builder, or a `Result` holding it for objects whose conversion
+    /// can fail.
+    type Output;
+
+    /// Turn this object into a builder for writing a G-code file.
+    fn to_gcode(self) -> Self::Output;
+}
+
+impl WriteGCode for Scene {
+    type Output = Result<GCodeWriter>;
+
+    fn to_gcode(self) -> Result<GCodeWriter> {
+        Ok(self.to_plot()?.optimize().to_gcode())
+    }
+}
+
+impl WriteGCode for Plot {
+    type Output = GCodeWriter;
+
+    fn to_gcode(self) -> GCodeWriter {
+        GCodeWriter {
+            plot: self,
+            pen_control: PenControl::ZAxis { up: 5., down: 0. },
+            draw_feed: 3000.,
+            travel_feed: 6000.,
+            pen_delay: 0.,
+            layout: Layout::default(),
+        }
+    }
+}
+
+impl GCodeWriter {
+    /// Raise and lower the pen with a servo, using the given `M3 S<value>` values.
+    pub fn servo(&mut self, up: f64, down: f64) -> &mut GCodeWriter {
+        self.pen_control = PenControl::Servo { up, down };
+        self
+    }
+
+    /// Raise and lower the pen by moving the Z axis to the given heights.
+    pub fn z_axis(&mut self, up: f64, down: f64) -> &mut GCodeWriter {
+        self.pen_control = PenControl::ZAxis { up, down };
+        self
+    }
+
+    /// Set the feed rate in mm/min used while drawing.
+    pub fn draw_feed(&mut self, feed: f64) -> &mut GCodeWriter {
+        self.draw_feed = feed;
+        self
+    }
+
+    /// Set the feed rate in mm/min used for pen-up moves.
+    pub fn travel_feed(&mut self, feed: f64) -> &mut GCodeWriter {
+        self.travel_feed = feed;
+        self
+    }
+
+    /// Set a pause in seconds after each pen movement, to let a servo settle.
+    pub fn pen_delay(&mut self, seconds: f64) -> &mut GCodeWriter {
+        self.pen_delay = seconds;
+        self
+    }
+
+    /// Set the size of the page in mm.
+    pub fn page_size(&mut self, width: f64, height: f64) -> &mut GCodeWriter {
+        self.paper(Paper::new(width, height))
+    }
+
+    /// Set the size of the paper. The default is `Paper::AXIDRAW`.
+    pub fn paper(&mut self, paper: Paper) -> &mut GCodeWriter {
+        self.layout.paper = paper;
+        self
+    }
+
+    /// Leave the given margins blank. The drawing is fitted to the space
+    /// inside them. Margins that leave no space on the page make `render`
+    /// return `Error::MarginsTooLarge`.
+    pub fn margins(&mut self, margins: Margins) -> &mut GCodeWriter {
+        self.layout.margins = margins;
+        self
+    }
+
+    /// Set the fraction of the space inside the margins that a fitted
+    /// drawing fills in its critical dimension. The default is 0.9.
+    pub fn fill_fraction(&mut self, fill_fraction: f64) -> &mut GCodeWriter {
+        self.layout.fill_fraction = fill_fraction;
+        self
+    }
+
+    /// Set where the drawing is placed inside the margins. The default is
+    /// `Align::Center`.
+    pub fn align(&mut self, align: Align) -> &mut GCodeWriter {
+        self.layout.align = align;
+        self
+    }
+
+    /// Set how plot units map to mm. With `Scale::Actual`, one plot unit is
+    /// drawn as one mm, and a drawing larger than the page goes past its
+    /// edges.
+    pub fn scale(&mut self, scale: Scale) -> &mut GCodeWriter {
+        self.layout.scale = scale;
+        self
+    }
+
+    /// The number of mm on the page that one plot unit is drawn as, for
+    /// example to flatten curves to a tolerance in mm with
+    /// `PathBuilder::scale`.
+    pub fn page_scale(&self) -> Result<f64> {
+        Ok(PageTransform::new(&self.plot, &self.layout)?.scale())
+    }
+
+    fn pen_up(&self, out: &mut String) {
+        match self.pen_control {
+            PenControl::Servo { up, .. } => writeln!(out, "M3 S{}", up).unwrap(),
+            PenControl::ZAxis { up, .. } => writeln!(out, "G0 Z{:.3}", up).unwrap(),
+        }
+        self.pause(out);
+    }
+
+    fn pen_down(&self, out: &mut String) {
+        match self.pen_control {
+            PenControl::Servo { down, .. } => writeln!(out, "M3 S{}", down).unwrap(),
+            PenControl::ZAxis { down, .. } => {
+                writeln!(out, "G1 Z{:.3} F{}", down, self.draw_feed).unwrap()
+            }
+        }
+        self.pause(out);
+    }
+
+    fn pause(&self, out: &mut String) {
+        if self.pen_delay > 0. {
+            writeln!(out, "G4 P{:.3}", self.pen_delay).unwrap();
+        }
+    }
+
+    /// Render the plot as a G-code program.
+    pub fn render(&self) -> Result<String> {
+        let transform = PageTransform::new(&self.plot, &self.layout)?;
+        let height = self.layout.paper.height;
+        // Flip the y axis, since page coordinates increase going down.
+        let to_machine = |p: Point| {
+            let p = transform.apply(p);
+            Point::new(p.x, height - p.y)
+        };
+
+        let mut out = String::new();
+        writeln!(out, "G21 ; units in mm").unwrap();
+        writeln!(out, "G90 ; absolute positioning").unwrap();
+        self.pen_up(&mut out);
+
+        for (i, layer) in self.plot.layers.iter().enumerate() {
+            if i > 0 {
+                writeln!(out, "G1 X0 Y0 F{}", self.travel_feed).unwrap();
+                writeln!(out, "M0 ; change to pen {} and resume", layer.pen).unwrap();
+            }
+            writeln!(out, "; pen {}", layer.pen).unwrap();
+
+            let mut last: Option<Point> = None;
+            for line in &layer.lines {
+                if last != Some(line.c1) {
+                    if last.is_some() {
+                        self.pen_up(&mut out);
+                    }
+                    let p = to_machine(line.c1);
+                    writeln!(out, "G1 X{:.3} Y{:.3} F{}", p.x, p.y, self.travel_feed).unwrap();
+                    self.pen_down(&mut out);
+                }
+
+                let p = to_machine(line.c2);
+                writeln!(out, "G1 X{:.3} Y{:.3} F{}", p.x, p.y, self.draw_feed).unwrap();
+                last = Some(line.c2);
+            }
+
+            if last.is_some() {
+                self.pen_up(&mut out);
+            }
+        }
+
+        writeln!(out, "G1 X0 Y0 F{}", self.travel_feed).unwrap();
+        writeln!(out, "M2").unwrap();
+        Ok(out)
+    }
+
+    /// Write the resulting G-code to a file at the given location.
+    pub fn save(&self, filename: &str) -> Result<()> {
+        std::fs::write(filename, self.render()?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::line_segment::LineSegment;
+    use crate::plot::Layer;
+    use crate::types::pt;
+
+    #[test]
+    fn test_layers_and_pen_moves() {
+        let mut l0 = Layer::new(0);
+        l0.lines.push(LineSegment::new(pt(0., 0.), pt(10., 0.)));
+        l0.lines.push(LineSegment::new(pt(10., 0.), pt(10., 10.)));
+        let mut l1 = Layer::new(1);
+        l1.lines.push(LineSegment::new(pt(0., 10.), pt(10., 10.)));
+        let plot = Plot::new(vec![l0, l1], pt(0., 0.), pt(10., 10.));
+
+        let gcode = plot
+            .to_gcode()
+            .page_size(10., 10.)
+            .servo(50., 30.)
+            .render()
+            .unwrap();
+        let lines: Vec<&str> = gcode.lines().collect();
+
+        assert_eq!(
+            vec![
+                "G21 ; units in mm",
+                "G90 ; absolute positioning",
+                "M3 S50",
+                "; pen 0",
+                "G1 X0.500 Y9.500 F6000",
+                "M3 S30",
+                "G1 X9.500 Y9.500 F3000",
+                "G1 X9.500 Y0.500 F3000",
+                "M3 S50",
+                "G1 X0 Y0 F6000",
+                "M0 ; change to pen 1 and resume",
+                "; pen 1",
+                "G1 X0.500 Y0.500 F6000",
+                "M3 S30",
+                "G1 X9.500 Y0.500 F3000",
+                "M3 S50",
+                "G1 X0 Y0 F6000",
+                "M2",
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut layer = Layer::new(0);
+        layer.lines.push(LineSegment::new(pt(0., 0.), pt(10., 10.)));
+        let plot = Plot::new(vec![layer], pt(0., 0.), pt(10., 10.));
+
+        // Fitted to the 20 x 10 mm space inside the margins, at its top left.
+        let gcode = plot
+            .to_gcode()
+            .paper(Paper::new(30., 20.))
+            .margins(Margins::uniform(5.))
+            .fill_fraction(1.)
+            .align(Align::TopLeft)
+            .render()
+            .unwrap();
+        assert!(gcode.contains("G1 X5.000 Y15.000 F6000"));
+        assert!(gcode.contains("G1 X15.000 Y5.000 F3000"));
+    }
+}
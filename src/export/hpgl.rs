@@ -0,0 +1,193 @@
+//! HPGL output for HP, Roland and compatible pen plotters.
+
+use crate::error::Result;
+use crate::export::page::{Align, Layout, Margins, PageTransform, Paper, Scale};
+use crate::plot::Plot;
+use crate::scene::Scene;
+use crate::types::Point;
+use std::fmt::Write;
+
+/// Plotter units per mm used by HP and most compatible plotters.
+const DEFAULT_UNITS_PER_MM: f64 = 40.;
+
+/// Builder for writing a `Plot` to an HPGL file.
+///
+/// Each `Layer` is drawn with pen `SP<n + 1>`, where `n` is the layer's pen,
+/// since HPGL reserves pen 0 for returning the pen to its stall. The plot is
+/// placed on the page in the same way as by `SVGWriter`.
+pub struct HPGLWriter {
+    /// The plot to write.
+    plot: Plot,
+    /// Placement of the plot on the page.
+    layout: Layout,
+    /// Number of plotter units in one mm.
+    units_per_mm: f64,
+}
+
+/// Objects that can be turned into an HPGL builder.
+pub trait WriteHPGL {
+    /// The builder, or a `Result` holding it for objects whose conversion
+    /// can fail.
+    type Output;
+
+    /// Turn this object into a builder for writing an HPGL file.
+    fn to_hpgl(self) -> Self::Output;
+}
+
+impl WriteHPGL for Scene {
+    type Output = Result<HPGLWriter>;
+
+    fn to_hpgl(self) -> Result<HPGLWriter> {
+        Ok(self.to_plot()?.optimize().to_hpgl())
+    }
+}
+
+impl WriteHPGL for Plot {
+    type Output = HPGLWriter;
+
+    fn to_hpgl(self) -> HPGLWriter {
+        HPGLWriter {
+            plot: self,
+            layout: Layout::default(),
+            units_per_mm: DEFAULT_UNITS_PER_MM,
+        }
+    }
+}
+
+impl HPGLWriter {
+    /// Set the size of the paper. The default is `Paper::AXIDRAW`.
+    pub fn paper(&mut self, paper: Paper) -> &mut HPGLWriter {
+        self.layout.paper = paper;
+        self
+    }
+
+    /// Leave the given margins blank. The drawing is fitted to the space
+    /// inside them. Margins that leave no space on the page make `render`
+    /// return `Error::MarginsTooLarge`.
+    pub fn margins(&mut self, margins: Margins) -> &mut HPGLWriter {
+        self.layout.margins = margins;
+        self
+    }
+
+    /// Set the fraction of the space inside the margins that a fitted
+    /// drawing fills in its critical dimension. The default is 0.9.
+    pub fn fill_fraction(&mut self, fill_fraction: f64) -> &mut HPGLWriter {
+        self.layout.fill_fraction = fill_fraction;
+        self
+    }
+
+    /// Set where the drawing is placed inside the margins. The default is
+    /// `Align::Center`.
+    pub fn align(&mut self, align: Align) -> &mut HPGLWriter {
+        self.layout.align = align;
+        self
+    }
+
+    /// Set how plot units map to mm. With `Scale::Actual`, one plot unit is
+    /// drawn as one mm, and a drawing larger than the page goes past its
+    /// edges.
+    pub fn scale(&mut self, scale: Scale) -> &mut HPGLWriter {
+        self.layout.scale = scale;
+        self
+    }
+
+    /// Set the resolution of the plotter, for plotters that do not use
+    /// the standard 40 units per mm.
+    pub fn units_per_mm(&mut self, units_per_mm: f64) -> &mut HPGLWriter {
+        self.units_per_mm = units_per_mm;
+        self
+    }
+
+    /// The number of mm on the page that one plot unit is drawn as, for
+    /// example to flatten curves to a tolerance in mm with
+    /// `PathBuilder::scale`.
+    pub fn page_scale(&self) -> Result<f64> {
+        Ok(PageTransform::new(&self.plot, &self.layout)?.scale())
+    }
+
+    /// Render the plot as HPGL commands.
+    pub fn render(&self) -> Result<String> {
+        let transform = PageTransform::new(&self.plot, &self.layout)?;
+        let height = self.layout.paper.height;
+        // HPGL's y axis increases going up, so flip it relative to page coordinates.
+        let to_units = |p: Point| {
+            let p = transform.apply(p);
+            (
+                (p.x * self.units_per_mm).round() as i64,
+                ((height - p.y) * self.units_per_mm).round() as i64,
+            )
+        };
+
+        let mut out = String::new();
+        out.push_str("IN;");
+
+        for layer in &self.plot.layers {
+            write!(out, "SP{};", layer.pen + 1).unwrap();
+
+            let mut last: Option<Point> = None;
+            for line in &layer.lines {
+                if last != Some(line.c1) {
+                    let (x, y) = to_units(line.c1);
+                    write!(out, "PU{},{};", x, y).unwrap();
+                }
+                let (x, y) = to_units(line.c2);
+                write!(out, "PD{},{};", x, y).unwrap();
+                last = Some(line.c2);
+            }
+
+            out.push_str("PU;");
+        }
+
+        out.push_str("SP0;\n");
+        Ok(out)
+    }
+
+    /// Write the resulting HPGL to a file at the given location.
+    pub fn save(&self, filename: &str) -> Result<()> {
+        std::fs::write(filename, self.render()?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::line_segment::LineSegment;
+    use crate::plot::Layer;
+    use crate::types::pt;
+
+    #[test]
+    fn test_pen_select_and_units() {
+        let mut l0 = Layer::new(0);
+        l0.lines.push(LineSegment::new(pt(0., 0.), pt(10., 0.)));
+        l0.lines.push(LineSegment::new(pt(10., 0.), pt(10., 10.)));
+        let mut l2 = Layer::new(2);
+        l2.lines.push(LineSegment::new(pt(0., 10.), pt(10., 10.)));
+        let plot = Plot::new(vec![l0, l2], pt(0., 0.), pt(10., 10.));
+
+        let hpgl = plot.to_hpgl().paper(Paper::new(10., 10.)).render().unwrap();
+
+        assert_eq!(
+            "IN;SP1;PU20,380;PD380,380;PD380,20;PU;SP3;PU20,20;PD380,20;PU;SP0;\n",
+            hpgl
+        );
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut layer = Layer::new(0);
+        layer.lines.push(LineSegment::new(pt(0., 0.), pt(10., 10.)));
+        let plot = Plot::new(vec![layer], pt(0., 0.), pt(10., 10.));
+
+        // Placed as by the G-code writer, in plotter units.
+        let hpgl = plot
+            .to_hpgl()
+            .paper(Paper::new(30., 20.))
+            .margins(Margins::uniform(5.))
+            .fill_fraction(1.)
+            .align(Align::TopLeft)
+            .render()
+            .unwrap();
+        assert_eq!("IN;SP1;PU200,600;PD600,200;PU;SP0;\n", hpgl);
+    }
+}
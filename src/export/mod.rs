@@ -1,146 +1,224 @@
-//! Tools for exporting plots. Currently supports SVG.
-
+//! Tools for exporting plots. Supports SVG for previewing and plotting
+//! through Inkscape, G-code for GRBL-style plotters and HPGL for HP and
+//! Roland pen plotters.
+
+pub mod gcode;
+pub mod hpgl;
+mod page;
+mod pen;
+
+use crate::error::Result;
+pub use crate::export::page::{Align, Margins, Paper, Scale};
+use crate::export::page::{Layout, PageTransform};
+use crate::export::pen::default_pens;
+pub use crate::export::pen::Pen;
+use crate::geom::line_segment::LineSegment;
 use crate::plot::Plot;
 use crate::scene::Scene;
-use crate::types::Point;
+use crate::types::{ObjectId, Point};
 use std::collections::HashMap;
 use svg::node::element::path::Data;
 use svg::node::element::Group;
 use svg::node::element::Path;
 use svg::Document;
 
-const DEFAULT_WIDTH: f64 = 300.;
-const DEFAULT_HEIGHT: f64 = 218.;
-
 /// Builder for writing a `Plot` to an `.svg` file.
 pub struct SVGWriter {
     /// The plot to write.
     plot: Plot,
-    /// A map from pen number to SVG color. This is used only to set the color
-    /// used for screen display, and does not affect the final plot.
-    layer_to_stroke: HashMap<usize, String>,
-    /// Fraction of the critical dimension to use; values less than 1 provide a
-    /// margin around the image.
-    fill_fraction: f64,
-    /// Width of the output in mm.
-    width: f64,
-    /// Height of the output in mm.
-    height: f64,
+    /// A map from pen number to the pen used to preview its layer. This does
+    /// not affect the final plot.
+    pens: HashMap<usize, Pen>,
+    /// Placement of the plot on the page.
+    layout: Layout,
+    /// Whether to write each scene object as its own path, labelled with its
+    /// ID.
+    object_ids: bool,
 }
 
 /// Objects that can be turned into an SVG builder.
 pub trait WriteSVG {
-    /// Turn this object into a builder for writing an `.svg` file.
-    fn to_svg(self) -> SVGWriter;
-}
+    /// The builder, or a `Result` holding it for objects whose conversion
+    /// can fail.
+    type Output;
 
-fn default_fills() -> HashMap<usize, String> {
-    [(0, "black".into()), (1, "red".into()), (2, "blue".into())]
-        .iter()
-        .cloned()
-        .collect()
+    /// Turn this object into a builder for writing an `.svg` file.
+    fn to_svg(self) -> Self::Output;
 }
 
 impl WriteSVG for Scene {
-    fn to_svg(self) -> SVGWriter {
-        self.to_plot().optimize().to_svg()
+    type Output = Result<SVGWriter>;
+
+    fn to_svg(self) -> Result<SVGWriter> {
+        Ok(self.to_plot()?.optimize().to_svg())
     }
 }
 
 impl WriteSVG for Plot {
+    type Output = SVGWriter;
+
     fn to_svg(self) -> SVGWriter {
         SVGWriter {
             plot: self,
-            layer_to_stroke: default_fills(),
-            fill_fraction: 0.9,
-            height: DEFAULT_HEIGHT,
-            width: DEFAULT_WIDTH,
+            pens: default_pens(),
+            layout: Layout::default(),
+            object_ids: false,
         }
     }
 }
 
 impl SVGWriter {
+    /// Use the drawing area of an AxiDraw V3 in portrait orientation.
     pub fn axidraw_portrait(&mut self) -> &mut SVGWriter {
-        self.width = DEFAULT_HEIGHT;
-        self.height = DEFAULT_WIDTH;
+        self.paper(Paper::AXIDRAW.portrait())
+    }
+
+    /// Set the size of the paper. The default is `Paper::AXIDRAW`.
+    pub fn paper(&mut self, paper: Paper) -> &mut SVGWriter {
+        self.layout.paper = paper;
+        self
+    }
+
+    /// Leave the given margins blank. The drawing is fitted to the space
+    /// inside them. Margins that leave no space on the page make `save`
+    /// return `Error::MarginsTooLarge`.
+    pub fn margins(&mut self, margins: Margins) -> &mut SVGWriter {
+        self.layout.margins = margins;
+        self
+    }
+
+    /// Set the fraction of the space inside the margins that a fitted
+    /// drawing fills in its critical dimension. The default is 0.9.
+    pub fn fill_fraction(&mut self, fill_fraction: f64) -> &mut SVGWriter {
+        self.layout.fill_fraction = fill_fraction;
+        self
+    }
+
+    /// Set where the drawing is placed inside the margins. The default is
+    /// `Align::Center`.
+    pub fn align(&mut self, align: Align) -> &mut SVGWriter {
+        self.layout.align = align;
+        self
+    }
+
+    /// Set how plot units map to mm. With `Scale::Actual`, one plot unit is
+    /// drawn as one mm, and a drawing larger than the page is cut off.
+    pub fn scale(&mut self, scale: Scale) -> &mut SVGWriter {
+        self.layout.scale = scale;
         self
     }
 
+    /// Preview the layer for the given pen number with the given pen. By
+    /// default, pens 0, 1 and 2 are black, red and blue, 0.3 mm wide.
+    pub fn pen(&mut self, number: usize, pen: Pen) -> &mut SVGWriter {
+        self.pens.insert(number, pen);
+        self
+    }
+
+    /// Preview the layers with the given pens, in order of pen number.
+    pub fn pens(&mut self, pens: Vec<Pen>) -> &mut SVGWriter {
+        self.pens = pens.into_iter().enumerate().collect();
+        self
+    }
+
+    /// Write the lines of each scene object as a separate path, with an `id`
+    /// of `object-{id}-{pen}` and a `class` of `object-{id}`, so that they
+    /// can be found in an editor. Lines that are not from a scene object are
+    /// written to a path without an ID.
+    pub fn object_ids(&mut self) -> &mut SVGWriter {
+        self.object_ids = true;
+        self
+    }
+
+    /// The number of mm on the page that one plot unit is drawn as, for
+    /// example to flatten curves to a tolerance in mm with
+    /// `PathBuilder::scale`.
+    pub fn page_scale(&self) -> Result<f64> {
+        Ok(PageTransform::new(&self.plot, &self.layout)?.scale())
+    }
+
     /// Write the resulting SVG to a file at the given location.
-    pub fn save(&self, filename: &str) {
-        let Plot {
-            lower_bound,
-            upper_bound,
-            layers,
-            ..
-        } = &self.plot;
-        let diff = upper_bound - lower_bound;
-        let w = diff.x;
-        let h = diff.y;
-        
-        let scale = (self.width / w).min(self.height / h) * self.fill_fraction;
-        let x_offset = (self.width - (scale * w)) / 2. - lower_bound.x * scale;
-        let y_offset = (self.height - (scale * h)) / 2. - lower_bound.y * scale;
-
-        let scale_point = |p: Point| (
-            p.x * scale + x_offset,
-            p.y * scale + y_offset
-        );
+    pub fn save(&self, filename: &str) -> Result<()> {
+        let transform = PageTransform::new(&self.plot, &self.layout)?;
+        let Paper { width, height } = self.layout.paper;
+        let scale_point = |p: Point| {
+            let p = transform.apply(p);
+            (p.x, p.y)
+        };
 
         let mut doc = Document::new()
-            .set(
-                "height",
-                format!("{}mm", self.height)
-            )
-            .set(
-                "width",
-                format!("{}mm", self.width)
-            )
-            .set(
-                "viewBox",
-                format!(
-                    "{} {} {} {}",
-                    0,
-                    0,
-                    self.width,
-                    self.height
-                ),
-            )
+            .set("height", format!("{}mm", height))
+            .set("width", format!("{}mm", width))
+            .set("viewBox", format!("{} {} {} {}", 0, 0, width, height))
             .set(
                 "xmlns:inkscape",
                 "http://www.inkscape.org/namespaces/inkscape",
             );
 
-        for layer in layers {
-            let mut path_data = Data::new();
-            let mut last: Option<Point> = None;
+        for layer in &self.plot.layers {
+            let objects = if self.object_ids {
+                group_by_object(&layer.lines)
+            } else {
+                vec![(None, layer.lines.iter().collect())]
+            };
+
+            let default_pen = Pen::default();
+            let pen = self.pens.get(&layer.pen).unwrap_or(&default_pen);
+            // Inkscape's AxiDraw extension selects layers by the number at
+            // the start of their label.
+            let mut g: Group = Group::new()
+                .set("inkscape:groupmode", "layer")
+                .set("inkscape:label", format!("{} {}", layer.pen, pen.name));
 
-            for line in &layer.lines {
-                if last != Some(line.c1) {
-                    path_data = path_data.move_to(scale_point(line.c1))
-                }
+            for (object, lines) in objects {
+                let mut path_data = Data::new();
+                let mut last: Option<Point> = None;
 
-                path_data = path_data.line_to(scale_point(line.c2));
+                for line in lines {
+                    if last != Some(line.c1) {
+                        path_data = path_data.move_to(scale_point(line.c1))
+                    }
 
-                last = Some(line.c2);
-            }
+                    path_data = path_data.line_to(scale_point(line.c2));
 
-            let d = String::from("black").clone();
-            let pen_color: &str = self.layer_to_stroke.get(&layer.pen).unwrap_or(&d);
-            let svg_line = Path::new()
-                .set("stroke", pen_color)
-                .set("fill", "none")
-                .set("vector-effect", "non-scaling-stroke")
-                .set("d", path_data);
+                    last = Some(line.c2);
+                }
 
-            let g: Group = Group::new()
-                .set("inkscape:groupmode", "layer")
-                .set("inkscape:label", format!("{}", layer.pen))
-                .add(svg_line);
+                let mut svg_line = Path::new()
+                    .set("stroke", pen.color.as_str())
+                    .set("stroke-width", pen.width)
+                    .set("stroke-linecap", "round")
+                    .set("stroke-linejoin", "round")
+                    .set("fill", "none")
+                    .set("d", path_data);
+                if let Some(object) = object {
+                    svg_line = svg_line
+                        .set("id", format!("object-{}-{}", object, layer.pen))
+                        .set("class", format!("object-{}", object));
+                }
+
+                g = g.add(svg_line);
+            }
 
             doc = doc.add(g);
         }
 
-        svg::save(filename, &doc).unwrap();
+        svg::save(filename, &doc)?;
+        Ok(())
+    }
+}
+
+/// Group lines by object in the order each object first appears, keeping
+/// the optimized order within each object.
+fn group_by_object(lines: &[LineSegment]) -> Vec<(Option<ObjectId>, Vec<&LineSegment>)> {
+    let mut index: HashMap<Option<ObjectId>, usize> = HashMap::new();
+    let mut objects: Vec<(Option<ObjectId>, Vec<&LineSegment>)> = Vec::new();
+    for line in lines {
+        let i = *index.entry(line.object).or_insert_with(|| {
+            objects.push((line.object, Vec::new()));
+            objects.len() - 1
+        });
+        objects[i].1.push(line);
     }
+    objects
 }
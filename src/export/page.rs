@@ -0,0 +1,274 @@
+//! Placement of a plot on a physical page, shared by all exporters.
+
+use crate::error::{Error, Result};
+use crate::plot::Plot;
+use crate::types::Point;
+
+/// A paper size in mm. Presets are given in landscape orientation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Paper {
+    /// Width of the paper in mm.
+    pub width: f64,
+    /// Height of the paper in mm.
+    pub height: f64,
+}
+
+impl Paper {
+    /// ISO A3, 420 x 297 mm.
+    pub const A3: Paper = Paper {
+        width: 420.,
+        height: 297.,
+    };
+    /// ISO A4, 297 x 210 mm.
+    pub const A4: Paper = Paper {
+        width: 297.,
+        height: 210.,
+    };
+    /// ISO A5, 210 x 148 mm.
+    pub const A5: Paper = Paper {
+        width: 210.,
+        height: 148.,
+    };
+    /// US Letter, 11 x 8.5 inches.
+    pub const LETTER: Paper = Paper {
+        width: 279.4,
+        height: 215.9,
+    };
+    /// The usable drawing area of an AxiDraw V3.
+    pub const AXIDRAW: Paper = Paper {
+        width: 300.,
+        height: 218.,
+    };
+    /// The usable drawing area of an AxiDraw V3/A3.
+    pub const AXIDRAW_A3: Paper = Paper {
+        width: 430.,
+        height: 297.,
+    };
+
+    /// Construct a custom paper size from a width and height in mm.
+    pub fn new(width: f64, height: f64) -> Paper {
+        Paper { width, height }
+    }
+
+    /// Return this paper size with the long side vertical.
+    pub fn portrait(self) -> Paper {
+        Paper {
+            width: self.width.min(self.height),
+            height: self.width.max(self.height),
+        }
+    }
+
+    /// Return this paper size with the long side horizontal.
+    pub fn landscape(self) -> Paper {
+        Paper {
+            width: self.width.max(self.height),
+            height: self.width.min(self.height),
+        }
+    }
+}
+
+/// Blank space in mm to leave on each side of the page.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Margins {
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub left: f64,
+}
+
+impl Margins {
+    /// Construct margins from the space on each side, in mm, clockwise from
+    /// the top.
+    pub fn new(top: f64, right: f64, bottom: f64, left: f64) -> Margins {
+        Margins {
+            top,
+            right,
+            bottom,
+            left,
+        }
+    }
+
+    /// Construct margins with the same space on every side.
+    pub fn uniform(margin: f64) -> Margins {
+        Margins::new(margin, margin, margin, margin)
+    }
+}
+
+/// Where a drawing is placed in the space inside the margins.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Align {
+    /// Center the drawing.
+    #[default]
+    Center,
+    /// Place the drawing in the top-left corner.
+    TopLeft,
+}
+
+/// How plot units are mapped to mm on the page.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Scale {
+    /// Scale the drawing uniformly to fit the space inside the margins.
+    #[default]
+    Fit,
+    /// Draw one plot unit as one mm, without scaling.
+    Actual,
+}
+
+/// Placement of a plot on a page.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Layout {
+    pub paper: Paper,
+    pub margins: Margins,
+    pub align: Align,
+    pub scale: Scale,
+    /// Fraction of the critical dimension to use when fitting; values less
+    /// than 1 leave space inside the margins.
+    pub fill_fraction: f64,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout {
+            paper: Paper::AXIDRAW,
+            margins: Margins::default(),
+            align: Align::Center,
+            scale: Scale::Fit,
+            fill_fraction: 0.9,
+        }
+    }
+}
+
+/// Maps plot coordinates to page coordinates in mm, with y increasing
+/// down the page.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PageTransform {
+    scale: f64,
+    x_offset: f64,
+    y_offset: f64,
+}
+
+impl PageTransform {
+    /// Place the bounds of `plot` on a page according to `layout`. When
+    /// fitting, bounds with no width (or no height) are fitted by their other
+    /// dimension; bounds with neither are an `Error::ZeroSize`. Margins that
+    /// leave no space on the paper are an `Error::MarginsTooLarge`.
+    pub(crate) fn new(plot: &Plot, layout: &Layout) -> Result<PageTransform> {
+        let diff = plot.upper_bound - plot.lower_bound;
+        let w = diff.x;
+        let h = diff.y;
+        if !(w.is_finite() && h.is_finite() && w >= 0. && h >= 0.) {
+            return Err(Error::ZeroSize);
+        }
+
+        let Margins {
+            top,
+            right,
+            bottom,
+            left,
+        } = layout.margins;
+        let width = layout.paper.width - left - right;
+        let height = layout.paper.height - top - bottom;
+        if !(width > 0. && height > 0.) {
+            return Err(Error::MarginsTooLarge);
+        }
+
+        let scale = match layout.scale {
+            Scale::Fit => {
+                if w == 0. && h == 0. {
+                    return Err(Error::ZeroSize);
+                }
+                let fit = |page: f64, size: f64| {
+                    if size > 0. {
+                        page / size
+                    } else {
+                        f64::INFINITY
+                    }
+                };
+                fit(width, w).min(fit(height, h)) * layout.fill_fraction
+            }
+            Scale::Actual => 1.,
+        };
+
+        let (x_space, y_space) = match layout.align {
+            Align::Center => ((width - scale * w) / 2., (height - scale * h) / 2.),
+            Align::TopLeft => (0., 0.),
+        };
+
+        Ok(PageTransform {
+            scale,
+            x_offset: left + x_space - plot.lower_bound.x * scale,
+            y_offset: top + y_space - plot.lower_bound.y * scale,
+        })
+    }
+
+    /// The number of mm on the page that one plot unit is drawn as.
+    pub(crate) fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Map a point from plot coordinates to page coordinates.
+    pub(crate) fn apply(&self, p: Point) -> Point {
+        Point::new(
+            p.x * self.scale + self.x_offset,
+            p.y * self.scale + self.y_offset,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pt;
+
+    #[test]
+    fn test_layout() {
+        let plot = Plot::new(vec![], pt(-5., -5.), pt(5., 15.));
+        let mut layout = Layout {
+            paper: Paper::new(100., 60.),
+            margins: Margins::new(10., 20., 10., 20.),
+            fill_fraction: 1.,
+            ..Layout::default()
+        };
+
+        // Fitted to the 60 x 40 mm space inside the margins, and centered.
+        let transform = PageTransform::new(&plot, &layout).unwrap();
+        assert_eq!(pt(40., 10.), transform.apply(pt(-5., -5.)));
+        assert_eq!(pt(60., 50.), transform.apply(pt(5., 15.)));
+
+        layout.align = Align::TopLeft;
+        let transform = PageTransform::new(&plot, &layout).unwrap();
+        assert_eq!(pt(20., 10.), transform.apply(pt(-5., -5.)));
+
+        layout.scale = Scale::Actual;
+        let transform = PageTransform::new(&plot, &layout).unwrap();
+        assert_eq!(pt(20., 10.), transform.apply(pt(-5., -5.)));
+        assert_eq!(pt(30., 30.), transform.apply(pt(5., 15.)));
+
+        // Margins wider than the paper would mirror the drawing.
+        layout.margins = Margins::new(10., 60., 10., 60.);
+        assert!(matches!(
+            PageTransform::new(&plot, &layout),
+            Err(Error::MarginsTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_zero_size() {
+        // A horizontal line is fitted by its width and centered vertically.
+        let line = Plot::new(vec![], pt(0., 5.), pt(10., 5.));
+        let layout = Layout {
+            paper: Paper::new(20., 20.),
+            fill_fraction: 1.,
+            ..Layout::default()
+        };
+        let transform = PageTransform::new(&line, &layout).unwrap();
+        assert_eq!(pt(0., 10.), transform.apply(pt(0., 5.)));
+        assert_eq!(pt(20., 10.), transform.apply(pt(10., 5.)));
+
+        let point = Plot::new(vec![], pt(1., 1.), pt(1., 1.));
+        assert!(matches!(
+            PageTransform::new(&point, &layout),
+            Err(Error::ZeroSize)
+        ));
+    }
+}
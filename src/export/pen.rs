@@ -0,0 +1,50 @@
+//! Pens used to preview a plot.
+
+use std::collections::HashMap;
+
+/// The nib width in mm of pens that are not given one.
+const DEFAULT_WIDTH: f64 = 0.3;
+
+/// A pen, as shown in a preview. This does not affect the final plot.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pen {
+    /// The SVG color of the ink.
+    pub color: String,
+    /// The width of the nib in mm.
+    pub width: f64,
+    /// A name for the pen, such as the model or ink.
+    pub name: String,
+}
+
+impl Pen {
+    /// Construct a pen with the given SVG color and nib width in mm, named
+    /// after its color.
+    pub fn new(color: &str, width: f64) -> Pen {
+        Pen {
+            color: color.into(),
+            width,
+            name: color.into(),
+        }
+    }
+
+    /// Return this pen with the given name.
+    pub fn named(mut self, name: &str) -> Pen {
+        self.name = name.into();
+        self
+    }
+}
+
+impl Default for Pen {
+    fn default() -> Self {
+        Pen::new("black", DEFAULT_WIDTH)
+    }
+}
+
+/// The pens used for the first few layers when no others are given.
+pub(crate) fn default_pens() -> HashMap<usize, Pen> {
+    ["black", "red", "blue"]
+        .iter()
+        .enumerate()
+        .map(|(i, color)| (i, Pen::new(color, DEFAULT_WIDTH)))
+        .collect()
+}
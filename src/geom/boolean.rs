@@ -0,0 +1,568 @@
+//! Boolean operations (union, intersection, difference and exclusive or)
+//! on polygons.
+//!
+//! The edges of each polygon are split where they cross the other polygon
+//! (see `clip`), and the parts that belong on the boundary of the result are
+//! kept. Degenerate cases, such as edges that overlap, are resolved as if the
+//! first polygon had been moved by an infinitesimal amount towards positive
+//! x, so that no edge lies exactly on the boundary of the other polygon.
+//! The kept parts are then traced into loops, and grouped into polygons.
+//!
+//! Outlines in the result run counter-clockwise (in a y-up coordinate
+//! system), and holes run clockwise.
+
+use crate::geom::clip::{clip_segment, clip_segment_shifted, crossings, Clip, Shift};
+use crate::geom::line_segment::{snap_endpoints, LineSegment};
+use crate::geom::polygon::{PointLoop, Polygon};
+use crate::geom::predicates::orient2d;
+use crate::types::Point;
+use rstar::{RTree, RTreeObject, AABB};
+use std::collections::HashMap;
+
+/// A boolean operation on two polygons.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BooleanOp {
+    /// The area covered by either polygon.
+    Union,
+    /// The area covered by both polygons.
+    Intersection,
+    /// The area covered by the first polygon but not the second.
+    Difference,
+    /// The area covered by exactly one of the polygons.
+    Xor,
+}
+
+/// Apply a boolean operation to two polygons.
+pub fn boolean(a: &Polygon, b: &Polygon, op: BooleanOp) -> Vec<Polygon> {
+    let (a, b) = (oriented(a), oriented(b));
+
+    let edges = match op {
+        BooleanOp::Union => {
+            let mut edges = parts(&a, &b, false, Shift::Negative);
+            edges.extend(parts(&b, &a, false, Shift::Positive));
+            edges
+        }
+        BooleanOp::Intersection => {
+            let mut edges = parts(&a, &b, true, Shift::Negative);
+            edges.extend(parts(&b, &a, true, Shift::Positive));
+            edges
+        }
+        BooleanOp::Difference => {
+            let mut edges = parts(&a, &b, false, Shift::Negative);
+            edges.extend(
+                parts(&b, &a, true, Shift::Positive)
+                    .iter()
+                    .map(LineSegment::reverse),
+            );
+            edges
+        }
+        BooleanOp::Xor => {
+            let mut result = boolean(&a, &b, BooleanOp::Difference);
+            result.extend(boolean(&b, &a, BooleanOp::Difference));
+            return result;
+        }
+    };
+
+    let epsilon = 1e-9 * extent(&a).max(extent(&b));
+    assemble(&trace(&cancel(snap_endpoints(&edges, epsilon))))
+}
+
+/// Return a copy of the polygon with its outline counter-clockwise and its
+/// holes clockwise.
+pub(crate) fn oriented(poly: &Polygon) -> Polygon {
+    let outline = if poly.points.signed_area() < 0. {
+        poly.points.reversed()
+    } else {
+        poly.points.clone()
+    };
+    let holes = poly
+        .holes
+        .iter()
+        .map(|h| {
+            if h.signed_area() > 0. {
+                h.reversed()
+            } else {
+                h.clone()
+            }
+        })
+        .collect();
+    Polygon {
+        points: outline,
+        holes,
+    }
+}
+
+/// The largest coordinate (in absolute value) of the outline of a polygon.
+fn extent(poly: &Polygon) -> f64 {
+    poly.points
+        .0
+        .iter()
+        .map(|p| p.x.abs().max(p.y.abs()))
+        .fold(0., f64::max)
+}
+
+/// The edges of a polygon, including its holes.
+fn edges(poly: &Polygon) -> Vec<LineSegment> {
+    std::iter::once(&poly.points)
+        .chain(&poly.holes)
+        .flat_map(|l| l.line_segments())
+        .collect()
+}
+
+/// The parts of the edges of `poly` that lie inside (or outside) of `other`,
+/// with `other` moved in the given direction.
+fn parts(poly: &Polygon, other: &Polygon, inside: bool, shift: Shift) -> Vec<LineSegment> {
+    let mut result = Vec::new();
+    for edge in edges(poly) {
+        match clip_segment_shifted(&edge, other, shift) {
+            Clip::Inside if inside => result.push(edge),
+            Clip::Outside if !inside => result.push(edge),
+            Clip::Split {
+                inside: i,
+                outside: o,
+            } => result.extend(if inside { i } else { o }),
+            _ => (),
+        }
+    }
+    result
+}
+
+/// A polygon's envelope with its index in a list.
+struct IndexedEnvelope {
+    envelope: AABB<[f64; 2]>,
+    index: usize,
+}
+
+impl RTreeObject for IndexedEnvelope {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// Compute the union of any number of polygons. Degenerate cases are
+/// resolved as if each polygon had been moved by an infinitesimal amount
+/// further towards positive x than the one after it.
+pub fn union_all(polys: &[Polygon]) -> Vec<Polygon> {
+    let polys: Vec<Polygon> = polys.iter().map(oriented).collect();
+    let tree = RTree::bulk_load(
+        polys
+            .iter()
+            .enumerate()
+            .map(|(index, p)| IndexedEnvelope {
+                envelope: p.envelope(),
+                index,
+            })
+            .collect(),
+    );
+
+    let mut result = Vec::new();
+    for (i, poly) in polys.iter().enumerate() {
+        for edge in edges(poly) {
+            // The parts of the edge covered by each other polygon, as pairs
+            // of fractions along the edge and points.
+            let mut covered: Vec<((f64, Point), (f64, Point))> = Vec::new();
+            for other in tree.locate_in_envelope_intersecting(&edge.envelope()) {
+                let j = other.index;
+                if j == i {
+                    continue;
+                }
+                let shift = if i < j {
+                    Shift::Negative
+                } else {
+                    Shift::Positive
+                };
+                let crossings = crossings(&edge, &polys[j], shift);
+                let mut inside = crossings.start_inside;
+                let mut last = (0., edge.c1);
+                for point in crossings.points {
+                    if inside {
+                        covered.push((last, point));
+                    }
+                    inside = !inside;
+                    last = point;
+                }
+                if inside {
+                    covered.push((last, (1., edge.c2)));
+                }
+            }
+
+            covered.sort_by(|a, b| (a.0).0.partial_cmp(&(b.0).0).unwrap());
+            let mut last = (0., edge.c1);
+            for (start, end) in covered {
+                if start.0 > last.0 && start.1 != last.1 {
+                    result.push(LineSegment {
+                        c1: last.1,
+                        c2: start.1,
+                        ..edge
+                    });
+                }
+                if end.0 > last.0 {
+                    last = end;
+                }
+            }
+            if last.0 < 1. && last.1 != edge.c2 {
+                result.push(LineSegment { c1: last.1, ..edge });
+            }
+        }
+    }
+
+    let epsilon = 1e-9 * polys.iter().map(extent).fold(0., f64::max);
+    assemble(&trace(&cancel(snap_endpoints(&result, epsilon))))
+}
+
+type Key = (u64, u64);
+
+fn key(p: &Point) -> Key {
+    (p.x.to_bits(), p.y.to_bits())
+}
+
+/// Remove pairs of edges that run between the same points in opposite
+/// directions, which occur where the two polygons touch along an edge.
+fn cancel(edges: Vec<LineSegment>) -> Vec<LineSegment> {
+    let mut counts: HashMap<(Key, Key), usize> = HashMap::new();
+    for l in &edges {
+        *counts.entry((key(&l.c1), key(&l.c2))).or_insert(0) += 1;
+    }
+    let mut to_cancel: HashMap<(Key, Key), usize> = counts
+        .iter()
+        .map(|(k, c)| (*k, *c.min(counts.get(&(k.1, k.0)).unwrap_or(&0))))
+        .collect();
+
+    edges
+        .into_iter()
+        .filter(|l| {
+            let remaining = to_cancel.get_mut(&(key(&l.c1), key(&l.c2))).unwrap();
+            if *remaining > 0 {
+                *remaining -= 1;
+                false
+            } else {
+                true
+            }
+        })
+        .collect()
+}
+
+/// Trace directed edges into closed loops. Where more than one edge leaves a
+/// point, the loop turns as far left as possible, so that loops which touch
+/// at a point are kept apart.
+fn trace(edges: &[LineSegment]) -> Vec<PointLoop> {
+    let mut leaving: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (i, l) in edges.iter().enumerate() {
+        leaving.entry(key(&l.c1)).or_default().push(i);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut loops = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+
+        let start = edges[first].c1;
+        let mut points = vec![start];
+        let mut current = edges[first];
+        let closed = loop {
+            if current.c2 == start {
+                break true;
+            }
+            points.push(current.c2);
+
+            let heading = current.vector();
+            let turn = |i: &usize| {
+                let v = edges[*i].vector();
+                (heading.x * v.y - heading.y * v.x).atan2(heading.dot(&v))
+            };
+            let next = leaving
+                .get(&key(&current.c2))
+                .into_iter()
+                .flatten()
+                .filter(|i| !used[**i])
+                .max_by(|a, b| turn(a).partial_cmp(&turn(b)).unwrap());
+            match next {
+                Some(i) => {
+                    used[*i] = true;
+                    current = edges[*i];
+                }
+                None => break false,
+            }
+        };
+
+        if closed {
+            let point_loop = simplify(points);
+            if point_loop.0.len() >= 3 && point_loop.signed_area() != 0. {
+                loops.push(point_loop);
+            }
+        }
+    }
+    loops
+}
+
+/// Remove points that lie on a straight line through their neighbours,
+/// including the tips of zero-width spikes.
+fn simplify(mut points: Vec<Point>) -> PointLoop {
+    let mut i = 0;
+    while i < points.len() && points.len() >= 3 {
+        let n = points.len();
+        let prev = points[(i + n - 1) % n];
+        let next = points[(i + 1) % n];
+        let p = points[i];
+        if orient2d(&prev, &p, &next) == 0. {
+            points.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+    PointLoop(points)
+}
+
+/// Group loops into polygons. Counter-clockwise loops are outlines, and each
+/// clockwise loop is a hole in the smallest outline that contains it.
+fn assemble(loops: &[PointLoop]) -> Vec<Polygon> {
+    let (outlines, holes): (Vec<&PointLoop>, Vec<&PointLoop>) =
+        loops.iter().partition(|l| l.signed_area() > 0.);
+
+    let mut polygons: Vec<Polygon> = outlines.iter().map(|l| Polygon::new(l.0.clone())).collect();
+
+    for hole in holes {
+        // A hole may touch its outline, even along an edge, so it is located
+        // by a point inside it rather than by its vertices. Outlines that are
+        // smaller than the hole can only be islands inside it.
+        let probe = interior_point(hole);
+        let probe = LineSegment::new(probe, probe);
+        let size = -hole.signed_area();
+        let owner = polygons
+            .iter_mut()
+            .filter(|p| p.points.signed_area() > size && clip_segment(&probe, p) == Clip::Inside)
+            .min_by(|a, b| {
+                a.points
+                    .signed_area()
+                    .partial_cmp(&b.points.signed_area())
+                    .unwrap()
+            });
+        if let Some(owner) = owner {
+            owner.holes.push(hole.clone());
+        }
+    }
+
+    polygons
+}
+
+/// Find a point inside a loop. A horizontal line is drawn midway between
+/// the two most widely separated heights of its vertices, and the point is
+/// the middle of the widest part of that line inside the loop.
+fn interior_point(point_loop: &PointLoop) -> Point {
+    let points = &point_loop.0;
+    let mut heights: Vec<f64> = points.iter().map(|p| p.y).collect();
+    heights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let y = heights
+        .windows(2)
+        .max_by(|a, b| (a[1] - a[0]).partial_cmp(&(b[1] - b[0])).unwrap())
+        .map_or(heights[0], |w| (w[0] + w[1]) / 2.);
+
+    let mut xs: Vec<f64> = point_loop
+        .line_segments()
+        .iter()
+        .filter(|l| (l.c1.y < y) != (l.c2.y < y))
+        .map(|l| l.c1.x + (y - l.c1.y) / (l.c2.y - l.c1.y) * (l.c2.x - l.c1.x))
+        .collect();
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let x = xs
+        .chunks(2)
+        .filter(|c| c.len() == 2)
+        .max_by(|a, b| (a[1] - a[0]).partial_cmp(&(b[1] - b[0])).unwrap())
+        .map_or(points[0].x, |c| (c[0] + c[1]) / 2.);
+
+    Point::new(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pt;
+    use proptest::prelude::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Polygon {
+        Polygon::from_coords(vec![
+            (x, y),
+            (x + size, y),
+            (x + size, y + size),
+            (x, y + size),
+        ])
+    }
+
+    fn area(polys: &[Polygon]) -> f64 {
+        polys
+            .iter()
+            .map(|p| p.points.signed_area() + p.holes.iter().map(|h| h.signed_area()).sum::<f64>())
+            .sum()
+    }
+
+    #[test]
+    fn test_overlapping_squares() {
+        let a = square(0., 0., 2.);
+        let b = square(1., 1., 2.);
+
+        let union = boolean(&a, &b, BooleanOp::Union);
+        assert_eq!(1, union.len());
+        assert_eq!(8, union[0].points.0.len());
+        assert!((area(&union) - 7.).abs() < 1e-9);
+
+        let intersection = boolean(&a, &b, BooleanOp::Intersection);
+        assert_eq!(1, intersection.len());
+        assert!((area(&intersection) - 1.).abs() < 1e-9);
+
+        let difference = boolean(&a, &b, BooleanOp::Difference);
+        assert_eq!(1, difference.len());
+        assert!((area(&difference) - 3.).abs() < 1e-9);
+
+        let xor = boolean(&a, &b, BooleanOp::Xor);
+        assert_eq!(2, xor.len());
+        assert!((area(&xor) - 6.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shared_edges() {
+        // Squares that touch along an edge merge into a rectangle.
+        let union = boolean(&square(0., 0., 1.), &square(1., 0., 1.), BooleanOp::Union);
+        assert_eq!(1, union.len());
+        assert_eq!(4, union[0].points.0.len());
+        assert!((area(&union) - 2.).abs() < 1e-12);
+
+        let intersection = boolean(
+            &square(0., 0., 1.),
+            &square(1., 0., 1.),
+            BooleanOp::Intersection,
+        );
+        assert!(intersection.is_empty());
+
+        // A polygon combined with itself, in either direction.
+        let a = square(0., 0., 1.);
+        let reversed = Polygon::new(a.points.reversed().0);
+        for b in &[&a, &reversed] {
+            assert!((area(&boolean(&a, b, BooleanOp::Union)) - 1.).abs() < 1e-12);
+            assert!((area(&boolean(&a, b, BooleanOp::Intersection)) - 1.).abs() < 1e-12);
+            assert!(boolean(&a, b, BooleanOp::Difference).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_holes() {
+        let outer = square(0., 0., 4.);
+        let inner = square(1., 1., 2.);
+
+        let ring = boolean(&outer, &inner, BooleanOp::Difference);
+        assert_eq!(1, ring.len());
+        assert_eq!(1, ring[0].holes.len());
+        assert!(ring[0].holes[0].signed_area() < 0.);
+        assert!((area(&ring) - 12.).abs() < 1e-12);
+
+        // Filling part of the hole back in.
+        let patched = boolean(&ring[0], &square(2., 2., 2.), BooleanOp::Union);
+        assert_eq!(1, patched.len());
+        assert_eq!(1, patched[0].holes.len());
+        assert_eq!(6, patched[0].holes[0].0.len());
+        assert!(patched[0].holes[0].contains(&pt(1.2, 1.2)));
+        assert!((area(&patched) - 13.).abs() < 1e-12);
+
+        // An island inside the hole.
+        let island = boolean(&ring[0], &square(1.5, 1.5, 1.), BooleanOp::Union);
+        assert_eq!(2, island.len());
+        assert!((area(&island) - 13.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_degenerate_shift() {
+        // Squares that share part of an edge overlap when the first is moved
+        // towards positive x, so they merge if the second is to its right,
+        // and are kept apart if it is to its left.
+        let a = square(0., 0., 1.);
+        let right = square(1., -0.5, 1.);
+        let left = square(-1., -0.5, 1.);
+        assert_eq!(1, boolean(&a, &right, BooleanOp::Union).len());
+        assert_eq!(2, boolean(&a, &left, BooleanOp::Union).len());
+        assert_eq!(2, boolean(&right, &a, BooleanOp::Union).len());
+        assert_eq!(1, boolean(&left, &a, BooleanOp::Union).len());
+
+        // With `union_all`, earlier polygons are moved further.
+        assert_eq!(1, union_all(&[a.clone(), right.clone()]).len());
+        assert_eq!(2, union_all(&[a.clone(), left.clone()]).len());
+        assert_eq!(2, union_all(&[right, a.clone()]).len());
+        assert_eq!(1, union_all(&[left, a]).len());
+    }
+
+    /// Star-shaped polygons, with vertices on a small integer grid so that
+    /// shared and overlapping edges are common, or at arbitrary coordinates.
+    fn polygons() -> impl Strategy<Value = Polygon> {
+        let coords = prop_oneof![
+            (0..8i32, 0..8i32).prop_map(|(x, y)| (x as f64, y as f64)),
+            (0.0..7.0f64, 0.0..7.0f64),
+        ];
+        prop::collection::vec(coords, 3..8)
+            .prop_map(|coords| {
+                let angle = |(x, y): &(f64, f64)| (y - 3.52).atan2(x - 3.51);
+                let mut coords = coords;
+                coords.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap());
+                coords.dedup_by(|a, b| angle(a) == angle(b));
+                Polygon::from_coords(coords)
+            })
+            .prop_filter("not star-shaped", |p| p.points.contains(&pt(3.51, 3.52)))
+    }
+
+    #[test]
+    fn test_union_all() {
+        // A ring of squares that overlap their neighbours, and one that is
+        // repeated.
+        let mut squares: Vec<Polygon> = (0..9)
+            .filter(|i| *i != 4)
+            .map(|i| square((i % 3) as f64, (i / 3) as f64, 1.5))
+            .collect();
+        squares.push(square(0., 0., 1.5));
+
+        let union = union_all(&squares);
+        assert_eq!(1, union.len());
+        assert_eq!(4, union[0].points.0.len());
+        assert_eq!(1, union[0].holes.len());
+        assert_eq!(4, union[0].holes[0].0.len());
+        assert!((area(&union) - 3.5 * 3.5 + 0.5 * 0.5).abs() < 1e-12);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_areas_add_up(a in polygons(), b in polygons()) {
+            let area_a = a.points.signed_area();
+            let area_b = b.points.signed_area();
+            let union = area(&boolean(&a, &b, BooleanOp::Union));
+            let intersection = area(&boolean(&a, &b, BooleanOp::Intersection));
+            let difference = area(&boolean(&a, &b, BooleanOp::Difference));
+            let xor = area(&boolean(&a, &b, BooleanOp::Xor));
+
+            prop_assert!((union + intersection - area_a - area_b).abs() < 1e-6);
+            prop_assert!((difference + intersection - area_a).abs() < 1e-6);
+            prop_assert!((xor + intersection - union).abs() < 1e-6);
+            prop_assert!(intersection >= -1e-9 && intersection <= area_a.min(area_b) + 1e-6);
+        }
+
+        #[test]
+        fn prop_union_all_matches_inclusion_exclusion(polys in prop::collection::vec(polygons(), 1..4)) {
+            // Inclusion-exclusion over the pairwise and triple overlaps.
+            let mut expected: f64 = polys.iter().map(|p| p.points.signed_area()).sum();
+            for i in 0..polys.len() {
+                for j in i + 1..polys.len() {
+                    let overlap = boolean(&polys[i], &polys[j], BooleanOp::Intersection);
+                    expected -= area(&overlap);
+                    for third in &polys[j + 1..] {
+                        for piece in &overlap {
+                            expected += area(&boolean(piece, third, BooleanOp::Intersection));
+                        }
+                    }
+                }
+            }
+
+            prop_assert!((area(&union_all(&polys)) - expected).abs() < 1e-6);
+        }
+    }
+}
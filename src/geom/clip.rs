@@ -0,0 +1,242 @@
+//! Clipping of line segments against polygons.
+//!
+//! Which side of the segment each polygon vertex lies on is decided with
+//! exact orientation predicates (see `predicates`). Degenerate cases are
+//! resolved as if the polygon had been moved by an infinitesimal amount
+//! towards negative x (and an infinitely smaller amount towards negative y).
+//! For example, a segment running along the edge of a square with the
+//! smaller x or y coordinate is inside it, and one running along the edge
+//! with the larger x or y coordinate is outside it.
+
+use crate::geom::line_segment::LineSegment;
+use crate::geom::polygon::Polygon;
+use crate::geom::predicates::{orient2d, orient2d_perturbed};
+use crate::types::{Point, Vector};
+
+/// The result of clipping a line segment against a polygon.
+#[derive(Debug, PartialEq)]
+pub enum Clip {
+    /// The segment lies entirely inside the polygon.
+    Inside,
+    /// The segment lies entirely outside the polygon, and does not touch it.
+    Outside,
+    /// The segment meets the boundary of the polygon, and is split into the
+    /// parts inside and outside of the polygon.
+    Split {
+        inside: Vec<LineSegment>,
+        outside: Vec<LineSegment>,
+    },
+}
+
+/// The direction in which a polygon is moved by an infinitesimal amount to
+/// resolve degenerate cases.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Shift {
+    /// Towards negative x (and y), as described above.
+    Negative,
+    /// Towards positive x (and y).
+    Positive,
+}
+
+/// The points where a line segment crosses the boundary of a polygon.
+pub(crate) struct Crossings {
+    /// Whether the start of the segment is inside the polygon.
+    pub start_inside: bool,
+    /// The crossings in order along the segment, as fractions along it and
+    /// as points.
+    pub points: Vec<(f64, Point)>,
+}
+
+/// Clip a line segment against a polygon (including its holes), using the
+/// even-odd rule. The parts of the segment keep its direction and pen.
+pub fn clip_segment(line: &LineSegment, poly: &Polygon) -> Clip {
+    clip_segment_shifted(line, poly, Shift::Negative)
+}
+
+/// Like `clip_segment`, with degenerate cases resolved by moving the polygon
+/// in the given direction.
+pub(crate) fn clip_segment_shifted(line: &LineSegment, poly: &Polygon, shift: Shift) -> Clip {
+    let crossings = crossings(line, poly, shift);
+    let mut inside = crossings.start_inside;
+    if line.c1 == line.c2 {
+        return if inside { Clip::Inside } else { Clip::Outside };
+    }
+
+    let mut inside_parts = Vec::new();
+    let mut outside_parts = Vec::new();
+    let mut last = line.c1;
+    for (_, point) in &crossings.points {
+        if *point != last {
+            let part = LineSegment {
+                c1: last,
+                c2: *point,
+                ..*line
+            };
+            if inside {
+                inside_parts.push(part);
+            } else {
+                outside_parts.push(part);
+            }
+            last = *point;
+        }
+        inside = !inside;
+    }
+    if last != line.c2 {
+        let part = LineSegment { c1: last, ..*line };
+        if inside {
+            inside_parts.push(part);
+        } else {
+            outside_parts.push(part);
+        }
+    }
+
+    if outside_parts.is_empty() {
+        Clip::Inside
+    } else if inside_parts.is_empty() && outside_parts.len() == 1 {
+        Clip::Outside
+    } else {
+        Clip::Split {
+            inside: inside_parts,
+            outside: outside_parts,
+        }
+    }
+}
+
+/// Find where a line segment crosses the boundary of a polygon (including its
+/// holes), with the polygon moved in the given direction.
+pub(crate) fn crossings(line: &LineSegment, poly: &Polygon, shift: Shift) -> Crossings {
+    // A zero-length segment is classified by the crossings before it, along
+    // a ray towards negative x.
+    let c1 = line.c1;
+    let c2 = if line.c1 == line.c2 {
+        line.c1 + Vector::new(1., 0.)
+    } else {
+        line.c2
+    };
+    let v = c2 - c1;
+
+    // Each polygon edge that crosses the line through the segment does so
+    // before, on or after the segment. This is decided exactly, and
+    // consistently with clipping the polygon edge against the segment.
+    let mut before = 0;
+    let mut points: Vec<(f64, Point)> = Vec::new();
+    for point_loop in std::iter::once(&poly.points).chain(&poly.holes) {
+        let loop_points = &point_loop.0;
+        let sides: Vec<bool> = loop_points
+            .iter()
+            .map(|p| {
+                shifted(
+                    orient2d(&c1, &c2, p),
+                    orient2d_perturbed(&c1, &c2, p),
+                    shift,
+                ) > 0.
+            })
+            .collect();
+
+        for i in 0..loop_points.len() {
+            let j = (i + 1) % loop_points.len();
+            if sides[i] == sides[j] {
+                continue;
+            }
+            let (p, q) = (&loop_points[i], &loop_points[j]);
+
+            // Moving the polygon one way is the same as moving the segment
+            // the other way.
+            let o1 = orient2d(p, q, &c1);
+            let s1 = shifted(o1, -orient2d_perturbed(p, q, &c1), shift);
+            let s2 = shifted(orient2d(p, q, &c2), -orient2d_perturbed(p, q, &c2), shift);
+            if (s1 > 0.) != (s2 > 0.) {
+                let point = crossing_point((&c1, &c2), (p, q));
+                let t = (point - c1).dot(&v) / v.dot(&v);
+                points.push((t.clamp(0., 1.), point));
+            } else if o1 == 0. || (o1 > 0.) != sides[j] {
+                // The crossing is at `c1 + v * s` for some `s`, and
+                // `orient2d(p, q, c1)` is `s` times the cross product of `v`
+                // and `q - p`, whose sign is given by the side `q` is on.
+                before += 1;
+            }
+        }
+    }
+
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    if line.c1 == line.c2 {
+        points.clear();
+    }
+
+    Crossings {
+        start_inside: before % 2 == 1,
+        points,
+    }
+}
+
+/// Resolve an orientation `det` that may be zero, given the orientation with
+/// the point moved towards negative x.
+fn shifted(det: f64, perturbed: f64, shift: Shift) -> f64 {
+    if det != 0. {
+        det
+    } else if shift == Shift::Negative {
+        perturbed
+    } else {
+        -perturbed
+    }
+}
+
+/// Find where two segments (or the lines through them) cross. The result is
+/// exactly the same whichever order the segments are given in, so that
+/// clipping either segment against the other gives the same point.
+fn crossing_point(e: (&Point, &Point), f: (&Point, &Point)) -> Point {
+    let key = |(a, b): (&Point, &Point)| [a.x, a.y, b.x, b.y];
+    let (s, o) = if key(e) <= key(f) { (e, f) } else { (f, e) };
+
+    // Endpoints that lie exactly on the other line are used as they are.
+    for (segment, other) in &[(s, o), (o, s)] {
+        for vertex in &[segment.0, segment.1] {
+            if orient2d(other.0, other.1, vertex) == 0. {
+                return **vertex;
+            }
+        }
+    }
+
+    // The ratio of the areas of the triangles formed by one segment and each
+    // endpoint of the other gives the fraction along the other.
+    let a1 = orient2d(o.0, o.1, s.0);
+    let a2 = orient2d(o.0, o.1, s.1);
+    let point = s.0 + (s.1 - s.0) * (a1 / (a1 - a2)).clamp(0., 1.);
+
+    // For nearly parallel segments this is inaccurate, so make sure that the
+    // point is within both segments.
+    let v = o.1 - o.0;
+    let u = (point - o.0).dot(&v) / v.dot(&v);
+    if (0. ..=1.).contains(&u) {
+        point
+    } else {
+        o.0 + v * u.clamp(0., 1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pt;
+
+    #[test]
+    fn test_boundary_segments() {
+        let square = Polygon::new(vec![pt(0., 0.), pt(10., 0.), pt(10., 10.), pt(0., 10.)]);
+
+        let bottom = LineSegment::new(pt(2., 0.), pt(8., 0.));
+        let top = LineSegment::new(pt(2., 10.), pt(8., 10.));
+        let left = LineSegment::new(pt(0., 2.), pt(0., 8.));
+        let right = LineSegment::new(pt(10., 2.), pt(10., 8.));
+
+        let reverse = |line: &LineSegment| LineSegment::new(line.c2, line.c1);
+
+        for line in &[bottom, left] {
+            assert_eq!(Clip::Inside, clip_segment(line, &square));
+            assert_eq!(Clip::Inside, clip_segment(&reverse(line), &square));
+        }
+        for line in &[top, right] {
+            assert_eq!(Clip::Outside, clip_segment(line, &square));
+            assert_eq!(Clip::Outside, clip_segment(&reverse(line), &square));
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Flattening of curves into sequences of points. Each function returns
+//! the points after the start of the curve, up to and including its end,
+//! such that no part of the curve is further than `tolerance` from the
+//! resulting polyline. A tolerance that is zero, negative or not a number
+//! gives the finest flattening allowed, of `MAX_STEPS` steps.
+
+use crate::types::{Point, Vector};
+use std::f64::consts::PI;
+
+/// The most steps that a single curve is flattened into.
+pub const MAX_STEPS: usize = 10_000;
+
+/// Round a number of steps up to a whole number between 1 and `MAX_STEPS`.
+fn clamp_steps(n: f64) -> usize {
+    if n.is_nan() || n >= MAX_STEPS as f64 {
+        MAX_STEPS
+    } else {
+        (n.ceil() as usize).max(1)
+    }
+}
+
+/// The number of uniform steps needed to flatten a Bézier curve of the
+/// given degree, from the largest second difference of its control points
+/// (Wang's formula).
+fn bezier_steps(degree: f64, second_difference: f64, tolerance: f64) -> usize {
+    if second_difference == 0. {
+        return 1;
+    }
+    clamp_steps((degree * (degree - 1.) / 8. * second_difference / tolerance).sqrt())
+}
+
+/// Flatten a quadratic Bézier curve from `p0` to `p2` with control point `p1`.
+pub fn flatten_quadratic(p0: Point, p1: Point, p2: Point, tolerance: f64) -> Vec<Point> {
+    let dd = (p0 - 2. * p1.coords + p2.coords).coords.norm();
+    let steps = bezier_steps(2., dd, tolerance);
+
+    (1..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            let mt = 1. - t;
+            Point::from(mt * mt * p0.coords + 2. * mt * t * p1.coords + t * t * p2.coords)
+        })
+        .collect()
+}
+
+/// Flatten a cubic Bézier curve from `p0` to `p3` with control points `p1` and `p2`.
+pub fn flatten_cubic(p0: Point, p1: Point, p2: Point, p3: Point, tolerance: f64) -> Vec<Point> {
+    let dd1 = (p0 - 2. * p1.coords + p2.coords).coords.norm();
+    let dd2 = (p1 - 2. * p2.coords + p3.coords).coords.norm();
+    let steps = bezier_steps(3., dd1.max(dd2), tolerance);
+
+    (1..=steps)
+        .map(|i| {
+            let t = i as f64 / steps as f64;
+            let mt = 1. - t;
+            Point::from(
+                mt * mt * mt * p0.coords
+                    + 3. * mt * mt * t * p1.coords
+                    + 3. * mt * t * t * p2.coords
+                    + t * t * t * p3.coords,
+            )
+        })
+        .collect()
+}
+
+/// Flatten an elliptical arc around `center`, with the given radii and rotation
+/// of the x axis in radians, from `start_angle` through `sweep` radians.
+pub fn flatten_ellipse(
+    center: Point,
+    radii: Vector,
+    rotation: f64,
+    start_angle: f64,
+    sweep: f64,
+    tolerance: f64,
+) -> Vec<Point> {
+    let r = radii.x.abs().max(radii.y.abs());
+    let step = if tolerance < r || tolerance.is_nan() {
+        2. * (1. - tolerance / r).acos()
+    } else {
+        PI / 2.
+    };
+    let steps = if sweep == 0. {
+        1
+    } else {
+        clamp_steps(sweep.abs() / step)
+    };
+    let (sin_r, cos_r) = rotation.sin_cos();
+
+    (1..=steps)
+        .map(|i| {
+            let theta = start_angle + sweep * i as f64 / steps as f64;
+            let x = radii.x * theta.cos();
+            let y = radii.y * theta.sin();
+            center + Vector::new(x * cos_r - y * sin_r, x * sin_r + y * cos_r)
+        })
+        .collect()
+}
+
+/// Flatten an elliptical arc given in SVG's endpoint parameterization, from
+/// `from` to `to`. Out-of-range radii are scaled up as the SVG specification
+/// requires, and an arc with a zero radius becomes a straight line.
+pub fn flatten_arc(
+    from: Point,
+    radii: Vector,
+    rotation: f64,
+    large_arc: bool,
+    sweep: bool,
+    to: Point,
+    tolerance: f64,
+) -> Vec<Point> {
+    let mut rx = radii.x.abs();
+    let mut ry = radii.y.abs();
+    if from == to {
+        return Vec::new();
+    }
+    if rx == 0. || ry == 0. {
+        return vec![to];
+    }
+
+    // Conversion to center parameterization, following appendix B.2.4 of the
+    // SVG 1.1 specification.
+    let (sin_r, cos_r) = rotation.sin_cos();
+    let half = (from - to) / 2.;
+    let x1 = cos_r * half.x + sin_r * half.y;
+    let y1 = -sin_r * half.x + cos_r * half.y;
+
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1. {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut coef = (num / den).max(0.).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+    let cx1 = coef * rx * y1 / ry;
+    let cy1 = -coef * ry * x1 / rx;
+
+    let mid = Point::from((from.coords + to.coords) / 2.);
+    let center = mid + Vector::new(cos_r * cx1 - sin_r * cy1, sin_r * cx1 + cos_r * cy1);
+
+    let angle = |v: Vector| v.y.atan2(v.x);
+    let start_angle = angle(Vector::new((x1 - cx1) / rx, (y1 - cy1) / ry));
+    let end_angle = angle(Vector::new((-x1 - cx1) / rx, (-y1 - cy1) / ry));
+    let mut delta = end_angle - start_angle;
+    if sweep && delta < 0. {
+        delta += 2. * PI;
+    } else if !sweep && delta > 0. {
+        delta -= 2. * PI;
+    }
+
+    let mut points = flatten_ellipse(
+        center,
+        Vector::new(rx, ry),
+        rotation,
+        start_angle,
+        delta,
+        tolerance,
+    );
+    // Land exactly on the endpoint.
+    if let Some(last) = points.last_mut() {
+        *last = to;
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bad_tolerance() {
+        let (p0, p1, p2) = (Point::new(0., 0.), Point::new(1., 2.), Point::new(2., 0.));
+        let center = Point::new(0., 0.);
+        for tolerance in &[0., -1., f64::NAN] {
+            assert_eq!(MAX_STEPS, flatten_quadratic(p0, p1, p2, *tolerance).len());
+            assert_eq!(MAX_STEPS, flatten_cubic(p0, p1, p2, p0, *tolerance).len());
+            let ellipse = flatten_ellipse(center, Vector::new(1., 2.), 0., 0., PI, *tolerance);
+            assert_eq!(MAX_STEPS, ellipse.len());
+        }
+
+        // Straight lines and empty arcs need only one step at any tolerance.
+        assert_eq!(1, flatten_quadratic(p0, Point::new(1., 0.), p2, 0.).len());
+        assert_eq!(
+            1,
+            flatten_ellipse(center, Vector::new(1., 2.), 0., 0., 0., 0.).len()
+        );
+        assert_eq!(1, flatten_cubic(p0, p1, p2, p0, f64::INFINITY).len());
+    }
+}
@@ -0,0 +1,154 @@
+//! Hatch patterns, which simulate a solid fill with a pen by covering the
+//! inside of a polygon with lines.
+
+use crate::geom::clip::{clip_segment, Clip};
+use crate::geom::line_segment::LineSegment;
+use crate::geom::offset::{offset, Join};
+use crate::geom::polygon::Polygon;
+use crate::types::{Point, Vector};
+
+/// The miter limit used for the corners of contour lines. Sharper corners
+/// are bevelled.
+const MITER_LIMIT: f64 = 4.;
+
+/// A pattern of lines used to hatch the inside of a polygon.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hatch {
+    /// Parallel lines at the given angle (in radians, counter-clockwise from
+    /// the x axis) and spacing.
+    Parallel { angle: f64, spacing: f64 },
+    /// Two sets of parallel lines, one at the given angle and one at right
+    /// angles to it.
+    Cross { angle: f64, spacing: f64 },
+    /// Lines that follow the outline of the polygon (and its holes), inset
+    /// from it by multiples of the spacing.
+    Contour { spacing: f64 },
+}
+
+impl Hatch {
+    /// Construct the hatch lines covering a polygon, with a given pen.
+    pub fn lines(&self, poly: &Polygon, pen: usize) -> Vec<LineSegment> {
+        match *self {
+            Hatch::Parallel { angle, spacing } => parallel_lines(poly, angle, spacing, pen),
+            Hatch::Cross { angle, spacing } => {
+                let mut lines = parallel_lines(poly, angle, spacing, pen);
+                lines.extend(parallel_lines(
+                    poly,
+                    angle + std::f64::consts::FRAC_PI_2,
+                    spacing,
+                    pen,
+                ));
+                lines
+            }
+            Hatch::Contour { spacing } => contour_lines(poly, spacing, pen),
+        }
+    }
+}
+
+/// Lines across the polygon at the given angle, placed on a grid anchored
+/// at the origin so that the hatching of neighbouring polygons lines up.
+fn parallel_lines(poly: &Polygon, angle: f64, spacing: f64, pen: usize) -> Vec<LineSegment> {
+    let mut result = Vec::new();
+    if spacing.is_nan() || spacing <= 0. || poly.points.0.is_empty() {
+        return result;
+    }
+
+    let direction = Vector::new(angle.cos(), angle.sin());
+    let normal = Vector::new(-direction.y, direction.x);
+
+    let extent = |v: &Vector| {
+        poly.points
+            .0
+            .iter()
+            .map(|p| p.coords.dot(v))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), d| {
+                (lo.min(d), hi.max(d))
+            })
+    };
+    let (start, end) = extent(&direction);
+    let (lower, upper) = extent(&normal);
+
+    let first = (lower / spacing).ceil() as i64;
+    let last = (upper / spacing).floor() as i64;
+    for i in first..=last {
+        let offset = normal * (i as f64 * spacing);
+        let line = LineSegment::new_with_pen(
+            Point::from(offset + direction * start),
+            Point::from(offset + direction * end),
+            pen,
+        );
+        match clip_segment(&line, poly) {
+            Clip::Inside => result.push(line),
+            Clip::Outside => (),
+            Clip::Split { inside, .. } => result.extend(inside),
+        }
+    }
+
+    result
+}
+
+/// Lines at each multiple of `spacing` inside the boundary of the polygon,
+/// following the outlines of the polygon shrunk by that distance.
+fn contour_lines(poly: &Polygon, spacing: f64, pen: usize) -> Vec<LineSegment> {
+    let mut result = Vec::new();
+    if spacing.is_nan() || spacing <= 0. {
+        return result;
+    }
+
+    for level in 1.. {
+        let inset = offset(
+            poly,
+            -spacing * level as f64,
+            Join::Miter { limit: MITER_LIMIT },
+        );
+        if inset.is_empty() {
+            break;
+        }
+        for p in &inset {
+            for l in std::iter::once(&p.points).chain(&p.holes) {
+                result.extend(l.line_segments_with_pen(pen));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pt;
+
+    fn length(lines: &[LineSegment]) -> f64 {
+        lines.iter().map(|l| l.vector().norm()).sum()
+    }
+
+    #[test]
+    fn test_parallel_with_hole() {
+        let poly = Polygon::with_holes(
+            vec![pt(0., 0.), pt(0., 10.), pt(10., 10.), pt(10., 0.)],
+            vec![vec![pt(2., 2.), pt(8., 2.), pt(8., 8.), pt(2., 8.)]],
+        );
+        let lines = Hatch::Parallel {
+            angle: 0.,
+            spacing: 1.,
+        }
+        .lines(&poly, 3);
+
+        // A line along the bottom edge of a polygon is inside it and one along
+        // the top edge is not (see `clip`), so there are lines from y = 0 to
+        // y = 9, and those from y = 2 to y = 7 are split by the hole.
+        assert_eq!(16, lines.len());
+        assert!((length(&lines) - 10. * 10. + 6. * 6.).abs() < 1e-9);
+        assert!(lines.iter().all(|l| l.pen == 3));
+    }
+
+    #[test]
+    fn test_contour_square() {
+        let poly = Polygon::new(vec![pt(0., 0.), pt(10., 0.), pt(10., 10.), pt(0., 10.)]);
+        let lines = Hatch::Contour { spacing: 2. }.lines(&poly, 0);
+
+        // Squares of side 6 and 2.
+        assert!((length(&lines) - 4. * 6. - 4. * 2.).abs() < 1e-9);
+    }
+}
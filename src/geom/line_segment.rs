@@ -2,8 +2,8 @@
 //! basic unit of a `Scene`. This is also where the heart of
 //! the intersection code lives (`LineSegment::intersect_segment`).
 
-use crate::types::{Point, PointContainer, Vector};
-use rstar::{RTreeObject, AABB};
+use crate::types::{ObjectId, Point, PointContainer, Vector};
+use rstar::{RTree, RTreeObject, AABB};
 
 /// Represents a two dimensional line segment, defined in terms of
 /// its endpoints.
@@ -15,6 +15,8 @@ pub struct LineSegment {
     pub c2: Point,
     /// The pen to use to draw this line segment.
     pub pen: usize,
+    /// The scene object this line segment came from, if any.
+    pub object: Option<ObjectId>,
 }
 
 impl PointContainer for LineSegment {
@@ -22,7 +24,7 @@ impl PointContainer for LineSegment {
         LineSegment {
             c1: self.c1.apply(lambda),
             c2: self.c2.apply(lambda),
-            pen: self.pen,
+            ..self
         }
     }
 }
@@ -41,14 +43,19 @@ impl Eq for LineSegment {}
 
 impl PartialOrd for LineSegment {
     fn partial_cmp(&self, other: &LineSegment) -> Option<std::cmp::Ordering> {
-        (self.c1.x, self.c1.y, self.c2.x, self.c2.y)
-            .partial_cmp(&(other.c1.x, other.c1.y, other.c2.x, other.c2.y))
+        Some(self.cmp(other))
     }
 }
 
+/// Orders segments by their endpoints, then by pen and object, so that it
+/// agrees with `PartialEq`.
 impl Ord for LineSegment {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        (self.c1.x, self.c1.y, self.c2.x, self.c2.y)
+            .partial_cmp(&(other.c1.x, other.c1.y, other.c2.x, other.c2.y))
+            .unwrap()
+            .then(self.pen.cmp(&other.pen))
+            .then(self.object.cmp(&other.object))
     }
 }
 
@@ -63,20 +70,25 @@ impl RTreeObject for LineSegment {
 impl LineSegment {
     /// Create a new line segment with the default pen.
     pub fn new(c1: Point, c2: Point) -> LineSegment {
-        LineSegment { c1, c2, pen: 0 }
+        LineSegment::new_with_pen(c1, c2, 0)
     }
 
     /// Create a new line segment with a given pen.
     pub fn new_with_pen(c1: Point, c2: Point, pen: usize) -> LineSegment {
-        LineSegment { c1, c2, pen }
+        LineSegment {
+            c1,
+            c2,
+            pen,
+            object: None,
+        }
     }
 
-    #[cfg(test)]
-    fn reverse(&self) -> LineSegment {
+    /// Construct the same line segment, drawn in the opposite direction.
+    pub fn reverse(&self) -> LineSegment {
         LineSegment {
             c1: self.c2,
             c2: self.c1,
-            pen: self.pen,
+            ..*self
         }
     }
 
@@ -85,10 +97,22 @@ impl LineSegment {
         self.c2 - self.c1
     }
 
+    /// Compute the shortest distance from a point to any point on the line segment.
+    pub fn distance_to(&self, point: &Point) -> f64 {
+        let v = self.vector();
+        let length_squared = v.dot(&v);
+        let t = if length_squared == 0. {
+            0.
+        } else {
+            ((point - self.c1).dot(&v) / length_squared).clamp(0., 1.)
+        };
+        (self.c1 + v * t - point).norm()
+    }
+
     pub fn point_side(&self, point: &Point) -> bool {
         let v1 = point - self.c1;
         let v2 = self.vector();
-        return v1.dot(&Vector::new(-v2.y, v2.x)) >= 0.;
+        v1.dot(&Vector::new(-v2.y, v2.x)) >= 0.
     }
 
     /// Returns the location at which two lines (extended to infinity) intersect, relative
@@ -137,10 +161,37 @@ impl LineSegment {
         }
 
         let f = ground_len / net_slope;
-        return Some(f / (self_rise * self_vec.norm()));
+        Some(f / (self_rise * self_vec.norm()))
     }
 }
 
+/// Move each endpoint onto the first endpoint seen within `epsilon` of it,
+/// so that endpoints that should meet are exactly equal, and drop segments
+/// that become empty.
+pub fn snap_endpoints(lines: &[LineSegment], epsilon: f64) -> Vec<LineSegment> {
+    let mut seen: RTree<[f64; 2]> = RTree::new();
+    let mut snap = |p: Point| {
+        if let Some(q) = seen.nearest_neighbor(&[p.x, p.y]) {
+            let q = Point::from(*q);
+            if (q - p).norm() <= epsilon {
+                return q;
+            }
+        }
+        seen.insert([p.x, p.y]);
+        p
+    };
+
+    lines
+        .iter()
+        .map(|line| LineSegment {
+            c1: snap(line.c1),
+            c2: snap(line.c2),
+            ..*line
+        })
+        .filter(|line| line.c1 != line.c2)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -259,4 +310,22 @@ mod tests {
         assert_close!(Some((0.5, false)), l2.intersect_segment(&l1));
     }
     */
+
+    #[test]
+    fn test_order_matches_eq() {
+        let line = LineSegment::new(pt(0., 0.), pt(1., 0.));
+        let other_pen = LineSegment { pen: 1, ..line };
+        let other_object = LineSegment {
+            object: Some(ObjectId(0)),
+            ..line
+        };
+
+        assert_ne!(line, other_pen);
+        assert_eq!(std::cmp::Ordering::Less, line.cmp(&other_pen));
+        assert_eq!(std::cmp::Ordering::Less, line.cmp(&other_object));
+        assert_eq!(
+            std::cmp::Ordering::Equal,
+            line.cmp(&LineSegment::new(pt(0., 0.), pt(1., 0.)))
+        );
+    }
 }
@@ -1,4 +1,12 @@
 //! Two dimensional drawing constructs.
 
+pub mod boolean;
+pub mod clip;
+pub mod curve;
+pub mod hatch;
 pub mod line_segment;
+pub mod offset;
+pub mod path;
 pub mod polygon;
+pub mod polyline;
+pub mod predicates;
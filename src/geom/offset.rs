@@ -0,0 +1,216 @@
+//! Offsetting (buffering) of polygons, which grows or shrinks a polygon by
+//! a fixed distance.
+//!
+//! An offset is built from pieces that cover the band swept by each edge as
+//! it moves outwards (or inwards), along with a piece filling the gap that
+//! opens up at each corner, whose shape depends on the join style. Growing
+//! a polygon takes the union of the polygon and the pieces; shrinking it
+//! subtracts the pieces. Loops that would cross themselves are split, and
+//! holes or outlines that collapse disappear, as part of the boolean
+//! operations.
+
+use crate::geom::boolean::{oriented, union_all};
+use crate::geom::polygon::{PointLoop, Polygon};
+use crate::types::{Point, Vector};
+
+/// The largest distance between a round join and the true arc, as a
+/// fraction of the offset distance.
+const ROUND_TOLERANCE: f64 = 0.01;
+
+/// How corners are filled in when the edges meeting there are moved apart.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Join {
+    /// Extend the edges until they meet. If the point where they meet is
+    /// further from the corner than `limit` times the offset distance, a
+    /// bevel is used instead.
+    Miter { limit: f64 },
+    /// Join the edges with an arc around the corner.
+    Round,
+    /// Join the edges with a straight line.
+    Bevel,
+}
+
+/// Offset a polygon by a distance, growing it if the distance is positive
+/// and shrinking it if it is negative. The result may be made of several
+/// polygons, or none at all.
+pub fn offset(poly: &Polygon, distance: f64, join: Join) -> Vec<Polygon> {
+    if distance == 0. || distance.is_nan() {
+        return vec![poly.clone()];
+    }
+
+    let poly = oriented(poly);
+    let pieces: Vec<Polygon> = std::iter::once(&poly.points)
+        .chain(&poly.holes)
+        .flat_map(|l| pieces(l, distance, join))
+        .collect();
+
+    if distance > 0. {
+        let mut polys = vec![poly];
+        polys.extend(pieces);
+        union_all(&polys)
+    } else {
+        let mut result = vec![poly];
+        for piece in union_all(&pieces) {
+            result = result.iter().flat_map(|r| r.difference(&piece)).collect();
+        }
+        result
+    }
+}
+
+/// The pieces covering the band between a loop and its offset. The loop
+/// must have the inside of the polygon to its left.
+fn pieces(point_loop: &PointLoop, distance: f64, join: Join) -> Vec<Polygon> {
+    let mut points: Vec<Point> = point_loop.0.clone();
+    points.dedup();
+    while points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let n = points.len();
+    if n < 2 {
+        return Vec::new();
+    }
+
+    // The unit normal to the right of each edge, pointing away from the
+    // inside of the polygon.
+    let normals: Vec<Vector> = (0..n)
+        .map(|i| {
+            let v = points[(i + 1) % n] - points[i];
+            Vector::new(v.y, -v.x) / v.norm()
+        })
+        .collect();
+
+    let mut result = Vec::new();
+    for i in 0..n {
+        let (a, b) = (points[i], points[(i + 1) % n]);
+        let shift = normals[i] * distance;
+        result.push(Polygon::new(vec![a, b, b + shift, a + shift]));
+
+        // The edges leaving this corner move apart if the corner is convex
+        // and the polygon is growing, or reflex and it is shrinking.
+        let (n1, n2) = (normals[(i + n - 1) % n], normals[i]);
+        let turn = n1.x * n2.y - n1.y * n2.x;
+        if turn * distance > 0. {
+            result.push(corner(a, n1, n2, distance, join));
+        }
+    }
+    result
+}
+
+/// The piece filling the gap at a corner between the offsets of an edge
+/// with normal `n1` and the following edge with normal `n2`.
+fn corner(v: Point, n1: Vector, n2: Vector, distance: f64, join: Join) -> Polygon {
+    let mut points = vec![v, v + n1 * distance];
+    match join {
+        Join::Miter { limit } => {
+            let cos = n1.dot(&n2);
+            if cos > -1. {
+                let miter = (n1 + n2) / (1. + cos);
+                if miter.norm() <= limit {
+                    points.push(v + miter * distance);
+                }
+            }
+        }
+        Join::Round => {
+            let start = n1.y.atan2(n1.x);
+            let mut sweep = n2.y.atan2(n2.x) - start;
+            if sweep * distance < 0. {
+                sweep += 2. * std::f64::consts::PI * distance.signum();
+            }
+            let step = 2. * (1. - ROUND_TOLERANCE).acos();
+            let steps = (sweep.abs() / step).ceil() as usize;
+            for k in 1..steps {
+                let angle = start + sweep * k as f64 / steps as f64;
+                points.push(v + Vector::new(angle.cos(), angle.sin()) * distance);
+            }
+        }
+        Join::Bevel => (),
+    }
+    points.push(v + n2 * distance);
+    Polygon::new(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pt;
+
+    fn square(size: f64) -> Polygon {
+        Polygon::from_coords(vec![(0., 0.), (size, 0.), (size, size), (0., size)])
+    }
+
+    fn area(polys: &[Polygon]) -> f64 {
+        polys
+            .iter()
+            .map(|p| {
+                p.points.signed_area().abs()
+                    - p.holes.iter().map(|h| h.signed_area().abs()).sum::<f64>()
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_grow_square() {
+        let miter = offset(&square(2.), 1., Join::Miter { limit: 2. });
+        assert_eq!(1, miter.len());
+        assert_eq!(4, miter[0].points.0.len());
+        assert!((area(&miter) - 16.).abs() < 1e-9);
+
+        let bevel = offset(&square(2.), 1., Join::Bevel);
+        assert_eq!(8, bevel[0].points.0.len());
+        assert!((area(&bevel) - 14.).abs() < 1e-9);
+
+        // A miter limit below the square root of two turns every corner
+        // into a bevel.
+        let limited = offset(&square(2.), 1., Join::Miter { limit: 1.2 });
+        assert!((area(&limited) - 14.).abs() < 1e-9);
+
+        let round = offset(&square(2.), 1., Join::Round);
+        let expected = 4. + 8. + std::f64::consts::PI;
+        assert!(area(&round) < expected);
+        assert!(area(&round) > expected - 0.05);
+    }
+
+    #[test]
+    fn test_shrink_with_hole() {
+        let ring = Polygon::with_holes(
+            square(10.).points.0,
+            vec![vec![pt(4., 4.), pt(6., 4.), pt(6., 6.), pt(4., 6.)]],
+        );
+
+        // The hole grows as the outline shrinks.
+        let thinner = offset(&ring, -1., Join::Miter { limit: 2. });
+        assert_eq!(1, thinner.len());
+        assert_eq!(1, thinner[0].holes.len());
+        assert!((area(&thinner) - 8. * 8. + 4. * 4.).abs() < 1e-9);
+
+        // Shrinking further leaves nothing.
+        assert!(offset(&ring, -3., Join::Bevel).is_empty());
+
+        // Growing fills in the hole.
+        let filled = offset(&ring, 1.5, Join::Round);
+        assert_eq!(1, filled.len());
+        assert!(filled[0].holes.is_empty());
+    }
+
+    #[test]
+    fn test_shrink_splits() {
+        // A dumbbell whose handle disappears, leaving two squares.
+        let dumbbell = Polygon::from_coords(vec![
+            (0., 0.),
+            (4., 0.),
+            (4., 1.5),
+            (6., 1.5),
+            (6., 0.),
+            (10., 0.),
+            (10., 4.),
+            (6., 4.),
+            (6., 2.5),
+            (4., 2.5),
+            (4., 4.),
+            (0., 4.),
+        ]);
+        let result = offset(&dumbbell, -1., Join::Miter { limit: 2. });
+        assert_eq!(2, result.len());
+        assert!((area(&result) - 2. * 2. * 2.).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,364 @@
+//! A builder for paths made of lines, Bézier curves and elliptical arcs.
+//! Curves are flattened as they are added, and the finished path is turned
+//! into polygons (from its closed subpaths) and open polylines.
+
+use crate::geom::curve::{flatten_arc, flatten_cubic, flatten_quadratic};
+use crate::geom::polygon::{PointLoop, Polygon};
+use crate::geom::polyline::Polyline;
+use crate::types::{Point, Vector};
+
+/// The default maximum distance between a curve and its flattened
+/// approximation, in mm on the page.
+const DEFAULT_TOLERANCE: f64 = 0.1;
+
+/// A flattened subpath.
+pub(crate) struct Subpath {
+    pub points: Vec<Point>,
+    pub closed: bool,
+}
+
+impl Subpath {
+    pub fn closed(points: Vec<Point>) -> Subpath {
+        Subpath {
+            points,
+            closed: true,
+        }
+    }
+}
+
+/// Builder for paths, with commands modelled on those of SVG paths. A path
+/// is made of subpaths, each started by `move_to` and optionally ended by
+/// `close`; a path that does not start with `move_to` starts at the origin.
+pub struct PathBuilder {
+    /// Maximum distance between a curve and its flattened approximation, in
+    /// mm on the page.
+    tolerance: f64,
+    /// The number of mm on the page that one scene unit is drawn as.
+    scale: f64,
+    /// Subpaths that have been finished.
+    subpaths: Vec<Subpath>,
+    /// The points of the subpath being built.
+    points: Vec<Point>,
+    /// The start of the subpath being built.
+    start: Point,
+    /// The end of the last command.
+    current: Point,
+}
+
+impl Default for PathBuilder {
+    fn default() -> Self {
+        PathBuilder::new()
+    }
+}
+
+impl PathBuilder {
+    /// Construct an empty path.
+    pub fn new() -> PathBuilder {
+        PathBuilder {
+            tolerance: DEFAULT_TOLERANCE,
+            scale: 1.,
+            subpaths: Vec::new(),
+            points: Vec::new(),
+            start: Point::origin(),
+            current: Point::origin(),
+        }
+    }
+
+    /// Set the maximum distance in mm on the page between a curve and its
+    /// flattened approximation. This applies to curves added afterwards.
+    pub fn tolerance(&mut self, tolerance: f64) -> &mut PathBuilder {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the number of mm on the page that one scene unit is drawn as, so
+    /// that curves are flattened to the tolerance on the page. This applies
+    /// to curves added afterwards. The default of 1 matches `Scale::Actual`;
+    /// for a drawing that is fitted to the page, use the `page_scale` of the
+    /// writer it is exported with.
+    pub fn scale(&mut self, scale: f64) -> &mut PathBuilder {
+        self.scale = scale;
+        self
+    }
+
+    /// Start a new subpath at the given point.
+    pub fn move_to(&mut self, to: Point) -> &mut PathBuilder {
+        self.finish(false);
+        self.start = to;
+        self.current = to;
+        self
+    }
+
+    /// Draw a straight line to the given point.
+    pub fn line_to(&mut self, to: Point) -> &mut PathBuilder {
+        self.extend(vec![to], to)
+    }
+
+    /// Draw a quadratic Bézier curve to the given point.
+    pub fn quad_to(&mut self, control: Point, to: Point) -> &mut PathBuilder {
+        let points = flatten_quadratic(self.current, control, to, self.scene_tolerance());
+        self.extend(points, to)
+    }
+
+    /// Draw a cubic Bézier curve to the given point.
+    pub fn cubic_to(&mut self, control1: Point, control2: Point, to: Point) -> &mut PathBuilder {
+        let points = flatten_cubic(self.current, control1, control2, to, self.scene_tolerance());
+        self.extend(points, to)
+    }
+
+    /// Draw an elliptical arc to the given point, as in SVG's `A` command.
+    /// The ellipse has the given radii, with its x axis rotated by `rotation`
+    /// radians. Of the four arcs that fit, `large_arc` selects one that
+    /// sweeps more than half way around, and `sweep` one that runs
+    /// counter-clockwise in a y-up coordinate system.
+    pub fn arc_to(
+        &mut self,
+        radii: Vector,
+        rotation: f64,
+        large_arc: bool,
+        sweep: bool,
+        to: Point,
+    ) -> &mut PathBuilder {
+        let points = flatten_arc(
+            self.current,
+            radii,
+            rotation,
+            large_arc,
+            sweep,
+            to,
+            self.scene_tolerance(),
+        );
+        self.extend(points, to)
+    }
+
+    /// Close the current subpath with a straight line back to its start.
+    pub fn close(&mut self) -> &mut PathBuilder {
+        self.finish(true);
+        self.current = self.start;
+        self
+    }
+
+    /// The closed subpaths, as loops of points.
+    pub fn loops(&self) -> Vec<PointLoop> {
+        self.subpaths()
+            .into_iter()
+            .filter(|(_, closed)| *closed)
+            .filter_map(|(points, _)| {
+                let mut points = points.to_vec();
+                if points.first() == points.last() {
+                    points.pop();
+                }
+                if points.len() > 2 {
+                    Some(PointLoop(points))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// The closed subpaths, as polygons. Loops inside other loops become
+    /// holes, and loops inside holes become new polygons.
+    pub fn polygons(&self) -> Vec<Polygon> {
+        nest_loops(self.loops())
+    }
+
+    /// The subpaths that are not closed, as polylines.
+    pub fn polylines(&self) -> Vec<Polyline> {
+        self.subpaths()
+            .into_iter()
+            .filter(|(_, closed)| !closed)
+            .map(|(points, _)| Polyline(points.to_vec()))
+            .collect()
+    }
+
+    /// Finish building, and return every subpath.
+    pub(crate) fn into_subpaths(mut self) -> Vec<Subpath> {
+        self.finish(false);
+        self.subpaths
+    }
+
+    /// The points of every subpath, including the one being built, and
+    /// whether each is closed.
+    fn subpaths(&self) -> Vec<(&[Point], bool)> {
+        let mut result: Vec<(&[Point], bool)> = self
+            .subpaths
+            .iter()
+            .map(|s| (&s.points[..], s.closed))
+            .collect();
+        if self.points.len() > 1 {
+            result.push((&self.points, false));
+        }
+        result
+    }
+
+    /// The tolerance in scene units.
+    fn scene_tolerance(&self) -> f64 {
+        self.tolerance / self.scale
+    }
+
+    /// Add points to the current subpath, ending at `to`.
+    fn extend(&mut self, points: Vec<Point>, to: Point) -> &mut PathBuilder {
+        if self.points.is_empty() {
+            self.points.push(self.current);
+        }
+        self.points.extend(points);
+        self.current = to;
+        self
+    }
+
+    /// End the current subpath, keeping it if it has more than one point.
+    fn finish(&mut self, closed: bool) {
+        let points = std::mem::take(&mut self.points);
+        if points.len() > 1 {
+            self.subpaths.push(Subpath { points, closed });
+        }
+    }
+}
+
+/// Group closed loops into polygons, treating loops that lie inside another
+/// polygon's outline as holes in it, and loops inside holes as new polygons.
+pub(crate) fn nest_loops(mut loops: Vec<PointLoop>) -> Vec<Polygon> {
+    loops.sort_by(|a, b| {
+        b.signed_area()
+            .abs()
+            .partial_cmp(&a.signed_area().abs())
+            .unwrap()
+    });
+
+    let mut polys: Vec<Polygon> = Vec::new();
+    for point_loop in loops {
+        let probe = point_loop.0[0];
+        let parent = polys.iter_mut().rev().find(|poly| {
+            poly.points.contains(&probe) && !poly.holes.iter().any(|h| h.contains(&probe))
+        });
+
+        match parent {
+            Some(poly) => {
+                let opposite = (poly.points.signed_area() > 0.) != (point_loop.signed_area() > 0.);
+                poly.holes.push(if opposite {
+                    point_loop
+                } else {
+                    point_loop.reversed()
+                });
+            }
+            None => polys.push(Polygon {
+                points: point_loop,
+                holes: Vec::new(),
+            }),
+        }
+    }
+
+    polys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::gcode::WriteGCode;
+    use crate::export::Paper;
+    use crate::scene::Scene;
+    use crate::types::{pt, vec};
+
+    #[test]
+    fn test_circle_with_hole() {
+        let mut path = PathBuilder::new();
+        path.tolerance(0.01)
+            .move_to(pt(-10., 0.))
+            .arc_to(vec(10., 10.), 0., false, true, pt(10., 0.))
+            .arc_to(vec(10., 10.), 0., false, true, pt(-10., 0.))
+            .close()
+            .move_to(pt(-1., -1.))
+            .line_to(pt(1., -1.))
+            .line_to(pt(1., 1.))
+            .line_to(pt(-1., 1.))
+            .close();
+
+        let polys = path.polygons();
+        assert_eq!(1, polys.len());
+        assert_eq!(1, polys[0].holes.len());
+        let area = polys[0].points.signed_area() + polys[0].holes[0].signed_area();
+        let expected = std::f64::consts::PI * 100. - 4.;
+        assert!(area < expected && area > expected - 0.02 * 2. * 10. * std::f64::consts::PI);
+        assert!(path.polylines().is_empty());
+    }
+
+    #[test]
+    fn test_open_curves() {
+        let mut path = PathBuilder::new();
+        path.line_to(pt(10., 0.))
+            .quad_to(pt(20., 0.), pt(20., 10.))
+            .move_to(pt(0., 20.))
+            .cubic_to(pt(5., 30.), pt(15., 10.), pt(20., 20.));
+
+        let polylines = path.polylines();
+        assert_eq!(2, polylines.len());
+        assert_eq!(pt(0., 0.), polylines[0].0[0]);
+        assert_eq!(pt(20., 10.), *polylines[0].0.last().unwrap());
+        assert!(polylines[0].0.len() > 3);
+        assert_eq!(pt(20., 20.), *polylines[1].0.last().unwrap());
+        assert!(path.loops().is_empty());
+    }
+
+    #[test]
+    fn test_tolerance_on_page() {
+        // A circle of radius 40 in a 100 unit frame, fitted to a small and a
+        // large page.
+        let tolerance = 0.05;
+        let mut counts = Vec::new();
+        for size in &[100., 400.] {
+            let frame = || {
+                let mut scene = Scene::new();
+                scene.crop(pt(0., 0.), pt(100., 100.));
+                scene
+            };
+            let paper = Paper::new(*size, *size);
+            let scale = frame()
+                .to_plot()
+                .unwrap()
+                .to_gcode()
+                .paper(paper)
+                .page_scale()
+                .unwrap();
+
+            let mut path = PathBuilder::new();
+            path.tolerance(tolerance)
+                .scale(scale)
+                .move_to(pt(10., 50.))
+                .arc_to(vec(40., 40.), 0., false, true, pt(90., 50.))
+                .arc_to(vec(40., 40.), 0., false, true, pt(10., 50.))
+                .close();
+            let mut scene = frame();
+            for poly in path.polygons() {
+                scene.stroke_poly(&poly, 0);
+            }
+            let gcode = scene.to_plot().unwrap().to_gcode().paper(paper).render();
+
+            // Every drawn segment stays within the tolerance of the circle,
+            // measured in mm on the page.
+            let center = pt(size / 2., size / 2.);
+            let radius = 40. * scale;
+            let mut last: Option<Point> = None;
+            let mut segments = 0;
+            for line in gcode.unwrap().lines() {
+                let words: Vec<&str> = line.split_whitespace().collect();
+                if words.len() != 4 || words[0] != "G1" {
+                    continue;
+                }
+                let p = pt(
+                    words[1][1..].parse().unwrap(),
+                    words[2][1..].parse().unwrap(),
+                );
+                if let (Some(q), "F3000") = (last, words[3]) {
+                    let midpoint = q + (p - q) / 2.;
+                    assert!(radius - (midpoint - center).norm() < tolerance + 1e-3);
+                    segments += 1;
+                }
+                last = Some(p);
+            }
+            counts.push(segments);
+        }
+        // The larger drawing needs more segments to meet the same tolerance.
+        assert!(counts[0] > 8 && counts[1] > counts[0]);
+    }
+}
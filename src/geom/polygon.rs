@@ -2,7 +2,9 @@
 
 use crate::types::{Point, PointContainer};
 
+use crate::geom::boolean::{boolean, BooleanOp};
 use crate::geom::line_segment::LineSegment;
+use crate::geom::offset::{offset, Join};
 use rstar::{RTreeObject, AABB};
 
 /// A vector of `Point`s, an underlying feature of a `Polygon`.
@@ -36,6 +38,48 @@ impl PointLoop {
 
         result
     }
+
+    /// Compute the signed area enclosed by this loop. The area is positive
+    /// if the points run counter-clockwise in a y-up coordinate system.
+    pub fn signed_area(&self) -> f64 {
+        let PointLoop(points) = self;
+        let mut area = 0.;
+        for (i, p) in points.iter().enumerate() {
+            let q = points[(i + 1) % points.len()];
+            area += p.x * q.y - q.x * p.y;
+        }
+        area / 2.
+    }
+
+    /// Determine whether a point lies inside this loop, using the even-odd rule.
+    pub fn contains(&self, point: &Point) -> bool {
+        let PointLoop(points) = self;
+        let mut inside = false;
+        let mut last = match points.last() {
+            Some(p) => p,
+            None => return false,
+        };
+        for p in points {
+            if (p.y > point.y) != (last.y > point.y)
+                && point.x < (last.x - p.x) * (point.y - p.y) / (last.y - p.y) + p.x
+            {
+                inside = !inside;
+            }
+            last = p;
+        }
+        inside
+    }
+
+    /// Return this loop with its points in the opposite direction.
+    pub fn reversed(&self) -> PointLoop {
+        PointLoop(self.0.iter().rev().cloned().collect())
+    }
+
+    /// Grow (or, with a negative distance, shrink) the area enclosed by this
+    /// loop by a distance. See `Polygon::offset`.
+    pub fn offset(&self, distance: f64, join: Join) -> Vec<Polygon> {
+        offset(&Polygon::new(self.0.clone()), distance, join)
+    }
 }
 
 /// Represents a two dimensional polygon.
@@ -52,7 +96,7 @@ pub struct Polygon {
 
 impl PointContainer for PointLoop {
     fn apply(self, lambda: &dyn Fn(Point) -> Point) -> Self {
-        PointLoop(self.0.into_iter().map(|p| lambda(p)).collect())
+        PointLoop(self.0.into_iter().map(lambda).collect())
     }
 }
 
@@ -115,4 +159,32 @@ impl Polygon {
             holes: holes.iter().map(|p| PointLoop(p.clone())).collect(),
         }
     }
+
+    /// Compute the union of this polygon and another.
+    pub fn union(&self, other: &Polygon) -> Vec<Polygon> {
+        boolean(self, other, BooleanOp::Union)
+    }
+
+    /// Compute the intersection of this polygon and another.
+    pub fn intersection(&self, other: &Polygon) -> Vec<Polygon> {
+        boolean(self, other, BooleanOp::Intersection)
+    }
+
+    /// Compute the area of this polygon that is not covered by another.
+    pub fn difference(&self, other: &Polygon) -> Vec<Polygon> {
+        boolean(self, other, BooleanOp::Difference)
+    }
+
+    /// Compute the area covered by exactly one of this polygon and another.
+    pub fn xor(&self, other: &Polygon) -> Vec<Polygon> {
+        boolean(self, other, BooleanOp::Xor)
+    }
+
+    /// Grow the polygon by a distance, or shrink it if the distance is
+    /// negative. Holes shrink as the polygon grows and grow as it shrinks.
+    /// Parts that become disconnected are returned as separate polygons,
+    /// and parts that vanish are dropped.
+    pub fn offset(&self, distance: f64, join: Join) -> Vec<Polygon> {
+        offset(self, distance, join)
+    }
 }
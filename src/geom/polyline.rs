@@ -0,0 +1,57 @@
+//! Represents an open path through a sequence of points.
+
+use crate::geom::line_segment::LineSegment;
+use crate::types::{Point, PointContainer};
+use rstar::{RTreeObject, AABB};
+
+/// A path through a sequence of points. Unlike a `PointLoop`, the last
+/// point is not joined back to the first.
+#[derive(Debug, Clone)]
+pub struct Polyline(pub Vec<Point>);
+
+impl Polyline {
+    /// Construct a polyline through the given points.
+    pub fn new(points: Vec<Point>) -> Polyline {
+        Polyline(points)
+    }
+
+    /// Construct a polyline from a vector of `(x, y)` tuples.
+    pub fn from_coords(coords: Vec<(f64, f64)>) -> Polyline {
+        Polyline(coords.into_iter().map(|(x, y)| Point::new(x, y)).collect())
+    }
+
+    /// Construct a vector of `LineSegments` representing this polyline, in
+    /// order, with the default pen.
+    pub fn line_segments(&self) -> Vec<LineSegment> {
+        self.line_segments_with_pen(0)
+    }
+
+    /// Construct a vector of `LineSegments` representing this polyline, in
+    /// order, with a given pen.
+    pub fn line_segments_with_pen(&self, pen: usize) -> Vec<LineSegment> {
+        self.0
+            .windows(2)
+            .map(|pair| LineSegment::new_with_pen(pair[0], pair[1], pen))
+            .collect()
+    }
+
+    /// Return this polyline with its points in the opposite direction.
+    pub fn reversed(&self) -> Polyline {
+        Polyline(self.0.iter().rev().cloned().collect())
+    }
+}
+
+impl PointContainer for Polyline {
+    fn apply(self, lambda: &dyn Fn(Point) -> Point) -> Self {
+        Polyline(self.0.into_iter().map(lambda).collect())
+    }
+}
+
+impl RTreeObject for Polyline {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        let points: Vec<[f64; 2]> = self.0.iter().map(|p| [p.x, p.y]).collect();
+        AABB::from_points(&points)
+    }
+}
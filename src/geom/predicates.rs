@@ -0,0 +1,133 @@
+//! Robust geometric predicates. These give the exact sign of the result,
+//! even for points that are nearly or exactly collinear, by falling back to
+//! exact arithmetic on floating-point expansions (after Shewchuk, "Adaptive
+//! Precision Floating-Point Arithmetic and Fast Robust Geometric Predicates")
+//! when the fast approximation is not known to be correct.
+
+use crate::types::Point;
+
+/// Relative error bound of the fast approximation in `orient2d`.
+const ORIENT_ERROR_BOUND: f64 = (3. + 16. * f64::EPSILON / 2.) * f64::EPSILON / 2.;
+
+/// Returns a positive value if `c` lies to the left of the directed line from
+/// `a` to `b` (i.e. `a`, `b`, `c` are counter-clockwise in a y-up coordinate
+/// system), a negative value if it lies to the right, and zero if the three
+/// points are collinear. The sign is always exact; the magnitude approximates
+/// twice the area of the triangle.
+pub fn orient2d(a: &Point, b: &Point, c: &Point) -> f64 {
+    let left = (a.x - c.x) * (b.y - c.y);
+    let right = (a.y - c.y) * (b.x - c.x);
+    let det = left - right;
+
+    let bound = ORIENT_ERROR_BOUND * (left.abs() + right.abs());
+    if det.abs() > bound || det.is_nan() {
+        return det;
+    }
+
+    orient2d_exact(a, b, c)
+}
+
+/// Like `orient2d`, but never returns zero: a point `c` on the line is
+/// classified as if it had been moved by an infinitesimal amount in the
+/// negative x and y directions (with the y offset infinitely smaller than the
+/// x offset). The result only depends on the undirected line, so swapping `a`
+/// and `b` always flips the sign.
+pub fn orient2d_perturbed(a: &Point, b: &Point, c: &Point) -> f64 {
+    let det = orient2d(a, b, c);
+    if det != 0. {
+        det
+    } else if b.y != a.y {
+        b.y - a.y
+    } else {
+        a.x - b.x
+    }
+}
+
+/// Compute `orient2d` exactly as the sum of six products.
+fn orient2d_exact(a: &Point, b: &Point, c: &Point) -> f64 {
+    let mut expansion: Vec<f64> = Vec::with_capacity(12);
+    let terms = [
+        (a.x, b.y),
+        (-a.x, c.y),
+        (-c.x, b.y),
+        (-a.y, b.x),
+        (a.y, c.x),
+        (c.y, b.x),
+    ];
+    for (x, y) in terms.iter() {
+        let (hi, lo) = two_product(*x, *y);
+        grow_expansion(&mut expansion, lo);
+        grow_expansion(&mut expansion, hi);
+    }
+
+    // Components are ordered by increasing magnitude and do not overlap,
+    // so the largest non-zero component has the sign of the sum.
+    expansion
+        .iter()
+        .rev()
+        .find(|x| **x != 0.)
+        .cloned()
+        .unwrap_or(0.)
+}
+
+/// Compute `a + b` as an unevaluated sum `hi + lo` with no rounding error.
+fn two_sum(a: f64, b: f64) -> (f64, f64) {
+    let hi = a + b;
+    let b_virtual = hi - a;
+    let a_virtual = hi - b_virtual;
+    let lo = (a - a_virtual) + (b - b_virtual);
+    (hi, lo)
+}
+
+/// Compute `a * b` as an unevaluated sum `hi + lo` with no rounding error.
+fn two_product(a: f64, b: f64) -> (f64, f64) {
+    let hi = a * b;
+    let lo = a.mul_add(b, -hi);
+    (hi, lo)
+}
+
+/// Add `b` to a non-overlapping expansion, keeping it non-overlapping and
+/// ordered by increasing magnitude.
+fn grow_expansion(expansion: &mut Vec<f64>, b: f64) {
+    let mut q = b;
+    for e in expansion.iter_mut() {
+        let (hi, lo) = two_sum(q, *e);
+        *e = lo;
+        q = hi;
+    }
+    expansion.push(q);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pt;
+
+    #[test]
+    fn test_nearly_collinear() {
+        // The naive determinant of these points rounds to the wrong sign or zero.
+        let a = pt(0.5, 0.5);
+        let b = pt(12., 12.);
+        let c = pt(24., 24.);
+        assert_eq!(0., orient2d(&a, &b, &c));
+
+        let c = pt(24., 24. + 24. * f64::EPSILON);
+        assert!(orient2d(&a, &b, &c) > 0.);
+        let c = pt(24., 24. - 12. * f64::EPSILON);
+        assert!(orient2d(&a, &b, &c) < 0.);
+    }
+
+    #[test]
+    fn test_perturbed_is_symmetric() {
+        let a = pt(0., 0.);
+        let b = pt(10., 0.);
+        let c = pt(5., 0.);
+        assert!(orient2d_perturbed(&a, &b, &c) < 0.);
+        assert!(orient2d_perturbed(&b, &a, &c) > 0.);
+
+        let b = pt(0., 10.);
+        let c = pt(0., 5.);
+        assert!(orient2d_perturbed(&a, &b, &c) > 0.);
+        assert!(orient2d_perturbed(&b, &a, &c) < 0.);
+    }
+}
@@ -0,0 +1,13 @@
+//! Tools for loading existing artwork into a scene. Supports SVG drawings,
+//! and OBJ and STL meshes.
+
+pub mod obj;
+pub mod stl;
+pub mod svg;
+
+use crate::error::Error;
+
+/// An `Error::Parse` with the given message.
+pub(crate) fn parse_error<E: std::fmt::Display>(error: E) -> Error {
+    Error::Parse(error.to_string())
+}
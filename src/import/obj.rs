@@ -0,0 +1,282 @@
+//! Reads polygon meshes from Wavefront `.obj` files.
+//!
+//! Only vertices (`v`), faces (`f`), objects (`o`), groups (`g`) and
+//! material names (`usemtl`) are read. Material names can select pens, but
+//! material libraries (`mtllib`) are not loaded, so colors and other
+//! material properties are ignored, as are texture coordinates, normals and
+//! free-form geometry. Coordinates are used as they are, so meshes exported with the
+//! y axis up need to be rotated to stand upright in a `Scene3`.
+
+use crate::draw_mode::DrawMode;
+use crate::error::Result;
+use crate::import::parse_error;
+use crate::projection::form::Form;
+use crate::projection::polygon3::Polygon3;
+use crate::projection::scene3::Scene3;
+use crate::projection::types3::Point3;
+use std::collections::HashMap;
+
+/// The faces of one object or group in an `.obj` file.
+pub struct ObjPart {
+    /// The name of the group, or of the object if the faces are not in a
+    /// group. Empty for faces outside of any object or group.
+    pub name: String,
+    /// The material of the faces, or an empty string if none was set.
+    pub material: String,
+    /// The faces, drawn with the pen mapped to the part's material or name.
+    pub form: Form,
+}
+
+/// Builder for reading meshes from an `.obj` file.
+#[derive(Default)]
+pub struct ObjReader {
+    /// Explicit pens for object and group names.
+    pens: HashMap<String, usize>,
+    /// Explicit pens for material names.
+    material_pens: HashMap<String, usize>,
+}
+
+impl ObjReader {
+    /// Construct a reader that draws every part with pen 0.
+    pub fn new() -> ObjReader {
+        ObjReader::default()
+    }
+
+    /// Draw the faces in the object or group with the given name with a pen.
+    /// Faces in a group use the group's pen if it has one, and otherwise the
+    /// pen of their object.
+    pub fn pen(&mut self, name: &str, pen: usize) -> &mut ObjReader {
+        self.pens.insert(name.to_string(), pen);
+        self
+    }
+
+    /// Draw the faces that use the material with the given name with a pen.
+    /// A material's pen takes precedence over the pen of the faces' group or
+    /// object.
+    pub fn material_pen(&mut self, name: &str, pen: usize) -> &mut ObjReader {
+        self.material_pens.insert(name.to_string(), pen);
+        self
+    }
+
+    /// Read every face in an `.obj` file at the given location as one form,
+    /// drawn with the default draw mode. Pens set with `pen` and
+    /// `material_pen` have no effect here; use `read_parts` or `read_into` to
+    /// draw parts with their pens.
+    pub fn read(&self, filename: &str) -> Result<Form> {
+        let content = std::fs::read_to_string(filename)?;
+        self.read_str(&content)
+    }
+
+    /// Read every face in a string containing an OBJ document as one form,
+    /// drawn with the default draw mode, as with `read`.
+    pub fn read_str(&self, content: &str) -> Result<Form> {
+        let polys = self
+            .read_parts_str(content)?
+            .into_iter()
+            .flat_map(|part| part.form.polys)
+            .collect();
+        Ok(Form::new(polys))
+    }
+
+    /// Read an `.obj` file at the given location as a form for each object
+    /// or group, in order of appearance. A new part starts whenever the
+    /// object, group or material changes, so a name can be used by several
+    /// parts.
+    pub fn read_parts(&self, filename: &str) -> Result<Vec<ObjPart>> {
+        let content = std::fs::read_to_string(filename)?;
+        self.read_parts_str(&content)
+    }
+
+    /// Read a string containing an OBJ document as a form for each object
+    /// or group, in order of appearance.
+    pub fn read_parts_str(&self, content: &str) -> Result<Vec<ObjPart>> {
+        let mut vertices: Vec<Point3> = Vec::new();
+        let mut parts: Vec<ObjPart> = Vec::new();
+        let mut object = String::new();
+        let mut group = String::new();
+        let mut material = String::new();
+        // Whether the last part holds the faces of the current object, group
+        // and material.
+        let mut in_part = false;
+
+        for (i, line) in content.lines().enumerate() {
+            let error = |message: &str| parse_error(format!("line {}: {}", i + 1, message));
+            let line = line.split('#').next().unwrap();
+            let mut tokens = line.split_whitespace();
+
+            match tokens.next() {
+                Some("v") => {
+                    let coords = tokens
+                        .take(3)
+                        .map(|t| t.parse::<f64>().map_err(|_| error("invalid vertex")))
+                        .collect::<Result<Vec<f64>>>()?;
+                    if coords.len() < 3 {
+                        return Err(error("vertex has fewer than three coordinates"));
+                    }
+                    vertices.push(Point3::new(coords[0], coords[1], coords[2]));
+                }
+                Some("f") => {
+                    let points = tokens
+                        .map(|t| {
+                            let index = t.split('/').next().unwrap();
+                            let index: i64 = index.parse().map_err(|_| error("invalid face"))?;
+                            // Indices start at 1, and negative indices count
+                            // back from the latest vertex.
+                            let index = if index < 0 {
+                                vertices.len() as i64 + index
+                            } else {
+                                index - 1
+                            };
+                            vertices
+                                .get(index as usize)
+                                .filter(|_| index >= 0)
+                                .copied()
+                                .ok_or_else(|| error("vertex index out of range"))
+                        })
+                        .collect::<Result<Vec<Point3>>>()?;
+                    if points.len() < 3 {
+                        continue;
+                    }
+
+                    if !in_part {
+                        let name = if group.is_empty() { &object } else { &group };
+                        let pen = self
+                            .material_pens
+                            .get(&material)
+                            .or_else(|| self.pens.get(&group))
+                            .or_else(|| self.pens.get(&object))
+                            .copied()
+                            .unwrap_or(0);
+                        parts.push(ObjPart {
+                            name: name.clone(),
+                            material: material.clone(),
+                            form: Form::new(Vec::new()).draw_mode(DrawMode {
+                                pen: Some(pen),
+                                ..Default::default()
+                            }),
+                        });
+                        in_part = true;
+                    }
+                    parts
+                        .last_mut()
+                        .unwrap()
+                        .form
+                        .polys
+                        .push(Polygon3::from_points(points));
+                }
+                Some("o") => {
+                    let name = tokens.collect::<Vec<_>>().join(" ");
+                    in_part &= name == object && group.is_empty();
+                    object = name;
+                    group = String::new();
+                }
+                Some("g") => {
+                    let name = tokens.collect::<Vec<_>>().join(" ");
+                    in_part &= name == group;
+                    group = name;
+                }
+                Some("usemtl") => {
+                    let name = tokens.collect::<Vec<_>>().join(" ");
+                    in_part &= name == material;
+                    material = name;
+                }
+                _ => (),
+            }
+        }
+
+        Ok(parts)
+    }
+
+    /// Read an `.obj` file and add each of its parts to a 3D scene.
+    pub fn read_into(&self, filename: &str, scene: &mut Scene3) -> Result<()> {
+        for part in self.read_parts(filename)? {
+            scene.add_form(part.form);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    const DOCUMENT: &str = "
+# A square pyramid and a floor.
+o pyramid
+v -1 -1 0
+v 1 -1 0
+v 1 1 0
+v -1 1 0
+v 0 0 1.0 1.0
+g sides
+f 1/1 2/2 5/3
+f 2//1 3//1 5//1
+f 3/1/1 4/1/1 5/1/1
+f -2 -5 -1
+g base
+f 4 3 2 1
+o floor
+v -2 -2 0
+v 2 -2 0
+v 2 2 0
+v -2 2 0
+f 6 7 8 9
+";
+
+    #[test]
+    fn test_parts() {
+        let parts = ObjReader::new()
+            .pen("base", 1)
+            .pen("floor", 2)
+            .read_parts_str(DOCUMENT)
+            .unwrap();
+
+        let names: Vec<&str> = parts.iter().map(|p| &*p.name).collect();
+        assert_eq!(vec!["sides", "base", "floor"], names);
+        let pens: Vec<Option<usize>> = parts.iter().map(|p| p.form.draw_mode.pen).collect();
+        assert_eq!(vec![Some(0), Some(1), Some(2)], pens);
+
+        let sides = &parts[0].form.polys;
+        assert_eq!(4, sides.len());
+        assert_eq!(Point3::new(-1., 1., 0.), sides[3].points[0]);
+        assert_eq!(Point3::new(0., 0., 1.), sides[3].points[2]);
+        assert!((Point3::new(0., -2. / 3., 1. / 3.) - sides[0].center).norm() < 1e-12);
+        assert_eq!(Point3::new(0., 0., 0.), parts[1].form.polys[0].center);
+
+        let form = ObjReader::new().read_str(DOCUMENT).unwrap();
+        assert_eq!(6, form.polys.len());
+
+        // Groups with the same name in different objects are separate parts.
+        let repeated = "v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+                        o a\ng sides\nf 1 2 3\no b\ng sides\nf 1 2 3\n";
+        let parts = ObjReader::new()
+            .pen("a", 1)
+            .pen("b", 2)
+            .read_parts_str(repeated)
+            .unwrap();
+        let pens: Vec<Option<usize>> = parts.iter().map(|p| p.form.draw_mode.pen).collect();
+        assert_eq!(vec![Some(1), Some(2)], pens);
+
+        // A material change splits a group, and its pen wins over the
+        // group's.
+        let materials = "v 0 0 0\nv 1 0 0\nv 0 1 0\n\
+                         g roof\nf 1 2 3\nusemtl red tile\nf 1 2 3\nusemtl\nf 1 2 3\n";
+        let parts = ObjReader::new()
+            .pen("roof", 1)
+            .material_pen("red tile", 2)
+            .read_parts_str(materials)
+            .unwrap();
+        let materials: Vec<&str> = parts.iter().map(|p| &*p.material).collect();
+        assert_eq!(vec!["", "red tile", ""], materials);
+        assert!(parts.iter().all(|p| p.name == "roof"));
+        let pens: Vec<Option<usize>> = parts.iter().map(|p| p.form.draw_mode.pen).collect();
+        assert_eq!(vec![Some(1), Some(2), Some(1)], pens);
+
+        assert!(ObjReader::new().read_str("v 0 0 0\nf 1 2 3").is_err());
+        assert!(matches!(
+            ObjReader::new().read_str("v 0 0 zero"),
+            Err(Error::Parse(_))
+        ));
+    }
+}
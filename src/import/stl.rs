@@ -0,0 +1,186 @@
+//! Reads triangle meshes from `.stl` files, in either the ASCII or the
+//! binary format.
+//!
+//! Faces are wound so that they are counter-clockwise as seen from the side
+//! their stored normal points towards. Colors and other attributes are
+//! ignored.
+
+use crate::error::Result;
+use crate::import::parse_error;
+use crate::projection::form::Form;
+use crate::projection::polygon3::Polygon3;
+use crate::projection::types3::{Point3, Vector3};
+
+/// Size of the header at the start of a binary STL file.
+const HEADER_SIZE: usize = 80;
+/// Size of each triangle in a binary STL file: a normal and three vertices
+/// of three `f32`s each, and a two byte attribute.
+const TRIANGLE_SIZE: usize = 50;
+
+/// Read the triangles in an `.stl` file at the given location.
+pub fn read_stl(filename: &str) -> Result<Form> {
+    let content = std::fs::read(filename)?;
+    read_stl_bytes(&content)
+}
+
+/// Read the triangles in the contents of an STL file. The format is
+/// detected from the contents.
+pub fn read_stl_bytes(content: &[u8]) -> Result<Form> {
+    // Binary files may also begin with `solid`, so check whether the size
+    // matches the triangle count first.
+    let binary_size = content
+        .get(HEADER_SIZE..HEADER_SIZE + 4)
+        .map(|count| HEADER_SIZE + 4 + TRIANGLE_SIZE * u32_at(count, 0) as usize);
+    let facets = if binary_size == Some(content.len()) || !content.starts_with(b"solid") {
+        binary_facets(content)?
+    } else {
+        let content = std::str::from_utf8(content).map_err(parse_error)?;
+        ascii_facets(content)?
+    };
+
+    Ok(Form::new(
+        facets
+            .into_iter()
+            .map(|(normal, mut points)| {
+                let poly = Polygon3::from_points(points.clone());
+                if poly.normal().dot(&normal) < 0. {
+                    points.reverse();
+                    return Polygon3::from_points(points);
+                }
+                poly
+            })
+            .collect(),
+    ))
+}
+
+/// The normal and vertices of each facet in a binary STL file.
+fn binary_facets(content: &[u8]) -> Result<Vec<(Vector3, Vec<Point3>)>> {
+    let count = match content.get(HEADER_SIZE..HEADER_SIZE + 4) {
+        Some(count) => u32_at(count, 0) as usize,
+        None => return Err(parse_error("missing STL header")),
+    };
+    let triangles = &content[HEADER_SIZE + 4..];
+    if triangles.len() < count * TRIANGLE_SIZE {
+        return Err(parse_error("STL file ends before its last triangle"));
+    }
+
+    let vector = |bytes: &[u8], offset: usize| {
+        Vector3::new(
+            f32_at(bytes, offset) as f64,
+            f32_at(bytes, offset + 4) as f64,
+            f32_at(bytes, offset + 8) as f64,
+        )
+    };
+    Ok(triangles
+        .chunks_exact(TRIANGLE_SIZE)
+        .take(count)
+        .map(|t| {
+            let points = (1..4).map(|i| Point3::from(vector(t, 12 * i))).collect();
+            (vector(t, 0), points)
+        })
+        .collect())
+}
+
+/// The normal and vertices of each facet in an ASCII STL file.
+fn ascii_facets(content: &str) -> Result<Vec<(Vector3, Vec<Point3>)>> {
+    let mut tokens = content.split_whitespace();
+    let mut facets = Vec::new();
+    let mut normal = Vector3::zeros();
+    let mut points = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "normal" => normal = vector(&mut tokens)?,
+            "vertex" => points.push(Point3::from(vector(&mut tokens)?)),
+            "endfacet" => {
+                if points.len() > 2 {
+                    facets.push((normal, points));
+                }
+                normal = Vector3::zeros();
+                points = Vec::new();
+            }
+            _ => (),
+        }
+    }
+    Ok(facets)
+}
+
+/// Parse the next three tokens as a vector.
+fn vector<'a>(tokens: &mut impl Iterator<Item = &'a str>) -> Result<Vector3> {
+    let mut coord = || -> Result<f64> {
+        let token = tokens
+            .next()
+            .ok_or_else(|| parse_error("STL file ends in the middle of a facet"))?;
+        token.parse().map_err(parse_error)
+    };
+    Ok(Vector3::new(coord()?, coord()?, coord()?))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    let mut word = [0; 4];
+    word.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(word)
+}
+
+fn f32_at(bytes: &[u8], offset: usize) -> f32 {
+    f32::from_bits(u32_at(bytes, offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    const ASCII: &str = "solid wedge
+  facet normal 0 0 -1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 0 1 0
+    endloop
+  endfacet
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 1
+      vertex 1 0 1
+      vertex 0 1 1
+    endloop
+  endfacet
+endsolid wedge
+";
+
+    #[test]
+    fn test_formats() {
+        let form = read_stl_bytes(ASCII.as_bytes()).unwrap();
+        assert_eq!(2, form.polys.len());
+        // The first facet is wound clockwise seen from above, to match its
+        // normal; the second is already counter-clockwise.
+        assert!(form.polys[0].normal().z < 0.);
+        assert!(form.polys[1].normal().z > 0.);
+        assert_eq!(Point3::new(1. / 3., 1. / 3., 1.), form.polys[1].center);
+
+        // The same triangles in the binary format, with a header that starts
+        // like an ASCII file.
+        let mut binary = b"solid but actually binary".to_vec();
+        binary.resize(HEADER_SIZE, 0);
+        binary.extend_from_slice(&2_u32.to_le_bytes());
+        for (normal, z) in &[(-1_f32, 0_f32), (1., 1.)] {
+            let floats = [0., 0., *normal, 0., 0., *z, 1., 0., *z, 0., 1., *z];
+            for f in &floats {
+                binary.extend_from_slice(&f.to_le_bytes());
+            }
+            binary.extend_from_slice(&[0, 0]);
+        }
+        let binary_form = read_stl_bytes(&binary).unwrap();
+        assert_eq!(2, binary_form.polys.len());
+        for (a, b) in binary_form.polys.iter().zip(&form.polys) {
+            assert_eq!(a.points, b.points);
+        }
+
+        assert!(read_stl_bytes(b"solid x facet normal 0 0 1 outer loop vertex 0 0").is_err());
+        assert!(matches!(
+            read_stl_bytes(&binary[..binary.len() - 1]),
+            Err(Error::Parse(_))
+        ));
+    }
+}
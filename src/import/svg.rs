@@ -0,0 +1,754 @@
+//! Reads paths and basic shapes from SVG files.
+//!
+//! Curves are flattened and transforms are applied, so that each element
+//! becomes either a `Polygon` (for closed shapes) or an open sequence of
+//! points. Text, images, and anything inside `<defs>` and similar
+//! non-rendered containers are ignored.
+
+use crate::draw_mode::{DrawMode, PenMask};
+use crate::error::Result;
+use crate::geom::curve::flatten_ellipse;
+use crate::geom::path::{nest_loops, PathBuilder, Subpath};
+use crate::geom::polygon::{PointLoop, Polygon};
+use crate::geom::polyline::Polyline;
+use crate::import::parse_error;
+use crate::scene::Scene;
+use crate::types::{ObjectId, Point, Vector};
+use ::svg::node::element::path::{Command, Data, Position};
+use ::svg::node::element::tag::Type;
+use ::svg::node::Attributes;
+use ::svg::parser::{Event, Parser};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+/// Elements whose contents are never rendered directly.
+const HIDDEN_CONTAINERS: &[&str] = &[
+    "defs", "clipPath", "mask", "marker", "pattern", "symbol", "metadata", "title", "desc",
+    "style", "script", "text",
+];
+
+/// Determines which attribute of an element selects its pen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PenSource {
+    /// Use the stroke color, or the fill color if the element has no stroke.
+    Color,
+    /// Use the `inkscape:label` of the enclosing Inkscape layer, falling back
+    /// to the color for elements outside of any layer.
+    Layer,
+}
+
+/// A shape read from an SVG file.
+#[derive(Debug)]
+pub enum ImportedShape {
+    /// A closed shape, which is filled if the SVG element had a fill.
+    Closed(Polygon, DrawMode),
+    /// An open path and the pen to draw it with.
+    Open(Polyline, usize),
+}
+
+impl ImportedShape {
+    /// Add this shape to a scene, returning its ID there.
+    pub fn add_to_scene(&self, scene: &mut Scene) -> ObjectId {
+        match self {
+            ImportedShape::Closed(poly, draw_mode) => {
+                scene.add_poly_with_draw_mode(poly, draw_mode.clone())
+            }
+            ImportedShape::Open(polyline, pen) => scene.add_polyline(polyline, *pen),
+        }
+    }
+}
+
+/// Builder for reading shapes from an `.svg` file.
+pub struct SVGReader {
+    /// Maximum distance between a curve and its flattened approximation,
+    /// in SVG user units.
+    tolerance: f64,
+    /// Which attribute selects the pen of each shape.
+    pen_source: PenSource,
+    /// Explicit pens for colors or layer labels.
+    pens: HashMap<String, usize>,
+}
+
+impl Default for SVGReader {
+    fn default() -> Self {
+        SVGReader::new()
+    }
+}
+
+impl SVGReader {
+    /// Construct a reader that assigns pens by color, with black, red and blue
+    /// mapped to pens 0, 1 and 2 to match the colors used by `SVGWriter`.
+    pub fn new() -> SVGReader {
+        let pens = [("#000000", 0), ("#ff0000", 1), ("#0000ff", 2)]
+            .iter()
+            .map(|(k, v)| (k.to_string(), *v))
+            .collect();
+
+        SVGReader {
+            tolerance: 0.1,
+            pen_source: PenSource::Color,
+            pens,
+        }
+    }
+
+    /// Set the maximum distance between a curve and its flattened approximation.
+    pub fn tolerance(&mut self, tolerance: f64) -> &mut SVGReader {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Select the attribute used to choose the pen of each shape.
+    pub fn pen_source(&mut self, pen_source: PenSource) -> &mut SVGReader {
+        self.pen_source = pen_source;
+        self
+    }
+
+    /// Map a color or a layer label to a pen. Layer labels that are numbers
+    /// map to that pen unless overridden, and other unknown colors and labels
+    /// are given unused pens in order of appearance.
+    pub fn pen(&mut self, key: &str, pen: usize) -> &mut SVGReader {
+        self.pens.insert(normalize_color(key), pen);
+        self
+    }
+
+    /// Read the shapes from an `.svg` file at the given location.
+    pub fn read(&self, filename: &str) -> Result<Vec<ImportedShape>> {
+        let content = std::fs::read_to_string(filename)?;
+        self.read_str(&content)
+    }
+
+    /// Read the shapes from a string containing an SVG document.
+    pub fn read_str(&self, content: &str) -> Result<Vec<ImportedShape>> {
+        let mut pens = PenAssigner {
+            pens: self.pens.clone(),
+        };
+        let mut stack: Vec<Context> = vec![Context::default()];
+        let mut shapes = Vec::new();
+
+        for event in Parser::new(content) {
+            let (name, kind, attributes) = match event {
+                Event::Tag(name, kind, attributes) => (name, kind, attributes),
+                Event::Error(error) => return Err(parse_error(error)),
+                _ => continue,
+            };
+
+            if kind == Type::End {
+                if stack.len() > 1 {
+                    stack.pop();
+                }
+                continue;
+            }
+
+            let context = stack.last().unwrap().child(name, &attributes);
+            if context.visible {
+                let subpaths = self.element_subpaths(name, &attributes, &context)?;
+                self.add_shapes(subpaths, &context, &mut pens, &mut shapes);
+            }
+
+            if kind == Type::Start {
+                stack.push(context);
+            }
+        }
+
+        Ok(shapes)
+    }
+
+    /// Read the shapes from an `.svg` file and add them to a scene.
+    pub fn read_into(&self, filename: &str, scene: &mut Scene) -> Result<()> {
+        for shape in self.read(filename)? {
+            shape.add_to_scene(scene);
+        }
+        Ok(())
+    }
+
+    fn element_subpaths(
+        &self,
+        name: &str,
+        attributes: &Attributes,
+        context: &Context,
+    ) -> Result<Vec<Subpath>> {
+        let tolerance = self.tolerance / context.transform.scale_factor();
+        let attr = |key: &str| attributes.get(key).map(|v| parse_length(v)).unwrap_or(0.);
+
+        let subpaths = match name {
+            "path" => match attributes.get("d") {
+                Some(d) => {
+                    let data = Data::parse(d).map_err(parse_error)?;
+                    path_subpaths(&data, tolerance)
+                }
+                None => Vec::new(),
+            },
+            "rect" => {
+                let (w, h) = (attr("width"), attr("height"));
+                let (rx, ry) = match (attributes.get("rx"), attributes.get("ry")) {
+                    (None, None) => (0., 0.),
+                    (Some(_), None) => (attr("rx"), attr("rx")),
+                    (None, Some(_)) => (attr("ry"), attr("ry")),
+                    _ => (attr("rx"), attr("ry")),
+                };
+                let points = rect_points(
+                    Point::new(attr("x"), attr("y")),
+                    w,
+                    h,
+                    Vector::new(rx.min(w / 2.), ry.min(h / 2.)),
+                    tolerance,
+                );
+                vec![Subpath::closed(points)]
+            }
+            "circle" => {
+                let r = attr("r");
+                let center = Point::new(attr("cx"), attr("cy"));
+                let points = flatten_ellipse(center, Vector::new(r, r), 0., 0., 2. * PI, tolerance);
+                vec![Subpath::closed(points)]
+            }
+            "ellipse" => {
+                let center = Point::new(attr("cx"), attr("cy"));
+                let radii = Vector::new(attr("rx"), attr("ry"));
+                let points = flatten_ellipse(center, radii, 0., 0., 2. * PI, tolerance);
+                vec![Subpath::closed(points)]
+            }
+            "line" => vec![Subpath {
+                points: vec![
+                    Point::new(attr("x1"), attr("y1")),
+                    Point::new(attr("x2"), attr("y2")),
+                ],
+                closed: false,
+            }],
+            "polyline" | "polygon" => {
+                let coords = attributes
+                    .get("points")
+                    .map(|v| parse_numbers(v))
+                    .unwrap_or_default();
+                vec![Subpath {
+                    points: coords
+                        .chunks_exact(2)
+                        .map(|c| Point::new(c[0], c[1]))
+                        .collect(),
+                    closed: name == "polygon",
+                }]
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(subpaths)
+    }
+
+    fn add_shapes(
+        &self,
+        subpaths: Vec<Subpath>,
+        context: &Context,
+        pens: &mut PenAssigner,
+        shapes: &mut Vec<ImportedShape>,
+    ) {
+        let filled = context.fill != "none";
+        let color = if context.stroke != "none" {
+            &context.stroke
+        } else if filled {
+            &context.fill
+        } else {
+            return;
+        };
+        let pen = match (self.pen_source, &context.layer) {
+            (PenSource::Layer, Some(label)) => pens.layer_pen(label),
+            _ => pens.color_pen(color),
+        };
+
+        let mut loops = Vec::new();
+        for subpath in subpaths {
+            let mut points: Vec<Point> = subpath
+                .points
+                .iter()
+                .map(|p| context.transform.apply(*p))
+                .collect();
+
+            if subpath.closed {
+                if points.len() > 1 && points.first() == points.last() {
+                    points.pop();
+                }
+                if points.len() > 2 {
+                    loops.push(PointLoop(points));
+                }
+            } else if points.len() > 1 {
+                shapes.push(ImportedShape::Open(Polyline(points), pen));
+            }
+        }
+
+        let draw_mode = DrawMode {
+            pen: Some(pen),
+            fill: filled,
+            hatch: None,
+            occludes: PenMask::All,
+        };
+        for poly in nest_loops(loops) {
+            shapes.push(ImportedShape::Closed(poly, draw_mode.clone()));
+        }
+    }
+}
+
+/// Hands out pens for colors and layer labels.
+struct PenAssigner {
+    pens: HashMap<String, usize>,
+}
+
+impl PenAssigner {
+    fn assign(&mut self, key: String, preferred: Option<usize>) -> usize {
+        if let Some(pen) = self.pens.get(&key) {
+            return *pen;
+        }
+        let pen = preferred.unwrap_or_else(|| self.pens.values().max().map(|p| p + 1).unwrap_or(0));
+        self.pens.insert(key, pen);
+        pen
+    }
+
+    fn color_pen(&mut self, color: &str) -> usize {
+        self.assign(normalize_color(color), None)
+    }
+
+    fn layer_pen(&mut self, label: &str) -> usize {
+        self.assign(label.to_string(), label.trim().parse().ok())
+    }
+}
+
+/// Drawing state inherited from enclosing elements.
+#[derive(Clone)]
+struct Context {
+    transform: Affine,
+    stroke: String,
+    fill: String,
+    layer: Option<String>,
+    visible: bool,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context {
+            transform: Affine::identity(),
+            stroke: "none".into(),
+            fill: "#000000".into(),
+            layer: None,
+            visible: true,
+        }
+    }
+}
+
+impl Context {
+    /// Compute the context of an element with the given attributes.
+    fn child(&self, name: &str, attributes: &Attributes) -> Context {
+        let mut context = self.clone();
+
+        if let Some(transform) = attributes.get("transform").and_then(|t| parse_transform(t)) {
+            context.transform = self.transform.then(&transform);
+        }
+        if let Some(stroke) = style_value(attributes, "stroke") {
+            context.stroke = normalize_color(&stroke);
+        }
+        if let Some(fill) = style_value(attributes, "fill") {
+            context.fill = normalize_color(&fill);
+        }
+        if name == "g" && attributes.get("inkscape:groupmode").map(|v| &**v) == Some("layer") {
+            if let Some(label) = attributes.get("inkscape:label") {
+                context.layer = Some(label.to_string());
+            }
+        }
+        if HIDDEN_CONTAINERS.contains(&name)
+            || style_value(attributes, "display").as_deref() == Some("none")
+            || style_value(attributes, "visibility").as_deref() == Some("hidden")
+        {
+            context.visible = false;
+        }
+
+        context
+    }
+}
+
+/// A 2D affine transformation in SVG's `matrix(a b c d e f)` form.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Affine([f64; 6]);
+
+impl Affine {
+    fn identity() -> Affine {
+        Affine([1., 0., 0., 1., 0., 0.])
+    }
+
+    /// Compose with a transform that is applied before this one.
+    fn then(&self, inner: &Affine) -> Affine {
+        let [a, b, c, d, e, f] = self.0;
+        let [a2, b2, c2, d2, e2, f2] = inner.0;
+        Affine([
+            a * a2 + c * b2,
+            b * a2 + d * b2,
+            a * c2 + c * d2,
+            b * c2 + d * d2,
+            a * e2 + c * f2 + e,
+            b * e2 + d * f2 + f,
+        ])
+    }
+
+    fn apply(&self, p: Point) -> Point {
+        let [a, b, c, d, e, f] = self.0;
+        Point::new(a * p.x + c * p.y + e, b * p.x + d * p.y + f)
+    }
+
+    /// The geometric mean of the scale along each axis.
+    fn scale_factor(&self) -> f64 {
+        let [a, b, c, d, _, _] = self.0;
+        let s = (a * d - b * c).abs().sqrt();
+        if s > 0. {
+            s
+        } else {
+            1.
+        }
+    }
+}
+
+/// Look up a presentation property, preferring the `style` attribute.
+fn style_value(attributes: &Attributes, name: &str) -> Option<String> {
+    if let Some(style) = attributes.get("style") {
+        for declaration in style.split(';') {
+            let mut parts = declaration.splitn(2, ':');
+            if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
+                if key.trim() == name {
+                    return Some(value.trim().to_string());
+                }
+            }
+        }
+    }
+    attributes.get(name).map(|v| v.trim().to_string())
+}
+
+/// Normalize a color to lowercase `#rrggbb` form where possible.
+fn normalize_color(color: &str) -> String {
+    let color = color.trim().to_lowercase();
+    let named = match color.as_str() {
+        "black" => "#000000",
+        "white" => "#ffffff",
+        "red" => "#ff0000",
+        "lime" => "#00ff00",
+        "green" => "#008000",
+        "blue" => "#0000ff",
+        "yellow" => "#ffff00",
+        "cyan" => "#00ffff",
+        "magenta" => "#ff00ff",
+        "gray" | "grey" => "#808080",
+        _ => "",
+    };
+    if !named.is_empty() {
+        return named.to_string();
+    }
+
+    let digits: Vec<char> = color.chars().skip(1).collect();
+    if color.starts_with('#') && digits.len() == 3 {
+        digits.iter().fold(String::from("#"), |mut s, c| {
+            s.push(*c);
+            s.push(*c);
+            s
+        })
+    } else {
+        color
+    }
+}
+
+/// Parse a list of numbers separated by whitespace and/or commas.
+fn parse_numbers(s: &str) -> Vec<f64> {
+    let mut numbers = Vec::new();
+    let mut current = String::new();
+    let mut last = ' ';
+
+    for c in s.chars() {
+        let starts_number = (c == '-' || c == '+') && last != 'e' && last != 'E'
+            || c == '.' && current.contains('.');
+        if c.is_whitespace() || c == ',' || starts_number {
+            if let Ok(n) = current.parse() {
+                numbers.push(n);
+            }
+            current.clear();
+        }
+        if !(c.is_whitespace() || c == ',') {
+            current.push(c);
+        }
+        last = c;
+    }
+    if let Ok(n) = current.parse() {
+        numbers.push(n);
+    }
+
+    numbers
+}
+
+/// Parse a length, ignoring any unit.
+fn parse_length(s: &str) -> f64 {
+    let end = s
+        .find(|c: char| c.is_alphabetic() && c != 'e' && c != 'E' || c == '%')
+        .unwrap_or(s.len());
+    s[..end].trim().parse().unwrap_or(0.)
+}
+
+/// Parse an SVG `transform` attribute.
+fn parse_transform(s: &str) -> Option<Affine> {
+    let mut result = Affine::identity();
+
+    for part in s.split(')') {
+        let mut pieces = part.splitn(2, '(');
+        let name = pieces.next()?.trim().trim_start_matches(',').trim();
+        if name.is_empty() {
+            continue;
+        }
+        let args = parse_numbers(pieces.next()?);
+        let arg = |i: usize, default: f64| args.get(i).cloned().unwrap_or(default);
+
+        let transform = match name {
+            "matrix" if args.len() == 6 => {
+                Affine([args[0], args[1], args[2], args[3], args[4], args[5]])
+            }
+            "translate" => Affine([1., 0., 0., 1., arg(0, 0.), arg(1, 0.)]),
+            "scale" => Affine([arg(0, 1.), 0., 0., arg(1, arg(0, 1.)), 0., 0.]),
+            "rotate" => {
+                let (sin, cos) = arg(0, 0.).to_radians().sin_cos();
+                let (cx, cy) = (arg(1, 0.), arg(2, 0.));
+                Affine([1., 0., 0., 1., cx, cy])
+                    .then(&Affine([cos, sin, -sin, cos, 0., 0.]))
+                    .then(&Affine([1., 0., 0., 1., -cx, -cy]))
+            }
+            "skewX" => Affine([1., 0., arg(0, 0.).to_radians().tan(), 1., 0., 0.]),
+            "skewY" => Affine([1., arg(0, 0.).to_radians().tan(), 0., 1., 0., 0.]),
+            _ => return None,
+        };
+        result = result.then(&transform);
+    }
+
+    Some(result)
+}
+
+/// Construct the outline of a rectangle, with corners rounded by the given radii.
+fn rect_points(origin: Point, w: f64, h: f64, r: Vector, tolerance: f64) -> Vec<Point> {
+    if r.x <= 0. || r.y <= 0. {
+        return vec![
+            origin,
+            origin + Vector::new(w, 0.),
+            origin + Vector::new(w, h),
+            origin + Vector::new(0., h),
+        ];
+    }
+
+    let corners = [
+        (origin + Vector::new(w - r.x, r.y), -PI / 2.),
+        (origin + Vector::new(w - r.x, h - r.y), 0.),
+        (origin + Vector::new(r.x, h - r.y), PI / 2.),
+        (origin + Vector::new(r.x, r.y), PI),
+    ];
+    let mut points = vec![origin + Vector::new(r.x, 0.)];
+    for (center, start) in corners.iter() {
+        points.extend(flatten_ellipse(*center, r, 0., *start, PI / 2., tolerance));
+    }
+    points
+}
+
+/// Flatten the subpaths of an SVG path, in the coordinates of the element
+/// it came from.
+fn path_subpaths(data: &Data, tolerance: f64) -> Vec<Subpath> {
+    let mut path = PathBuilder::new();
+    path.tolerance(tolerance);
+    let mut current = Point::origin();
+    let mut start = Point::origin();
+    // The last control point of a cubic or quadratic curve, for smooth curves.
+    let mut last_cubic: Option<Point> = None;
+    let mut last_quad: Option<Point> = None;
+
+    for command in data.iter() {
+        let (position, params) = match command {
+            Command::Close => {
+                path.close();
+                current = start;
+                last_cubic = None;
+                last_quad = None;
+                continue;
+            }
+            Command::Move(p, a)
+            | Command::Line(p, a)
+            | Command::HorizontalLine(p, a)
+            | Command::VerticalLine(p, a)
+            | Command::QuadraticCurve(p, a)
+            | Command::SmoothQuadraticCurve(p, a)
+            | Command::CubicCurve(p, a)
+            | Command::SmoothCubicCurve(p, a)
+            | Command::EllipticalArc(p, a) => (p, a),
+        };
+        let params: Vec<f64> = params.iter().map(|v| *v as f64).collect();
+        let relative = *position == Position::Relative;
+
+        let chunk_size = match command {
+            Command::HorizontalLine(..) | Command::VerticalLine(..) => 1,
+            Command::Move(..) | Command::Line(..) | Command::SmoothQuadraticCurve(..) => 2,
+            Command::QuadraticCurve(..) | Command::SmoothCubicCurve(..) => 4,
+            Command::CubicCurve(..) => 6,
+            _ => 7,
+        };
+
+        for (i, chunk) in params.chunks_exact(chunk_size).enumerate() {
+            let base = if relative {
+                current.coords
+            } else {
+                Vector::zeros()
+            };
+            let point = |j: usize| Point::new(chunk[j], chunk[j + 1]) + base;
+            let mut cubic_control = None;
+            let mut quad_control = None;
+
+            let end = match command {
+                Command::Move(..) if i == 0 => {
+                    start = point(0);
+                    path.move_to(start);
+                    start
+                }
+                Command::Move(..) | Command::Line(..) => {
+                    let end = point(0);
+                    path.line_to(end);
+                    end
+                }
+                Command::HorizontalLine(..) => {
+                    let x = if relative {
+                        current.x + chunk[0]
+                    } else {
+                        chunk[0]
+                    };
+                    let end = Point::new(x, current.y);
+                    path.line_to(end);
+                    end
+                }
+                Command::VerticalLine(..) => {
+                    let y = if relative {
+                        current.y + chunk[0]
+                    } else {
+                        chunk[0]
+                    };
+                    let end = Point::new(current.x, y);
+                    path.line_to(end);
+                    end
+                }
+                Command::QuadraticCurve(..) | Command::SmoothQuadraticCurve(..) => {
+                    let (control, end) = match command {
+                        Command::QuadraticCurve(..) => (point(0), point(2)),
+                        _ => (reflect(last_quad, current), point(0)),
+                    };
+                    path.quad_to(control, end);
+                    quad_control = Some(control);
+                    end
+                }
+                Command::CubicCurve(..) | Command::SmoothCubicCurve(..) => {
+                    let (c1, c2, end) = match command {
+                        Command::CubicCurve(..) => (point(0), point(2), point(4)),
+                        _ => (reflect(last_cubic, current), point(0), point(2)),
+                    };
+                    path.cubic_to(c1, c2, end);
+                    cubic_control = Some(c2);
+                    end
+                }
+                _ => {
+                    let end = point(5);
+                    path.arc_to(
+                        Vector::new(chunk[0], chunk[1]),
+                        chunk[2].to_radians(),
+                        chunk[3] != 0.,
+                        chunk[4] != 0.,
+                        end,
+                    );
+                    end
+                }
+            };
+
+            current = end;
+            last_cubic = cubic_control;
+            last_quad = quad_control;
+        }
+    }
+
+    path.into_subpaths()
+}
+
+/// Reflect the previous control point about the current point, as used by
+/// the smooth curve commands.
+fn reflect(control: Option<Point>, current: Point) -> Point {
+    match control {
+        Some(c) => current + (current - c),
+        None => current,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    const DOCUMENT: &str = r##"<svg xmlns="http://www.w3.org/2000/svg">
+        <defs><rect x="0" y="0" width="5" height="5"/></defs>
+        <g inkscape:groupmode="layer" inkscape:label="3" transform="translate(10, 20)">
+            <rect x="0" y="0" width="4" height="2" style="fill:none;stroke:#f00"/>
+            <path d="M 0 0 L 10 0 L 10 10 L 0 10 Z M 2 2 L 2 8 L 8 8 L 8 2 Z" fill="blue"/>
+        </g>
+        <polyline points="0,0 1,1 2,0" fill="none" stroke="green"/>
+        <circle cx="0" cy="0" r="1" fill="none" stroke="none"/>
+    </svg>"##;
+
+    #[test]
+    fn test_pens_by_color() {
+        let shapes = SVGReader::new().read_str(DOCUMENT).unwrap();
+        assert_eq!(3, shapes.len());
+
+        match &shapes[0] {
+            ImportedShape::Closed(poly, draw_mode) => {
+                assert_eq!(Some(1), draw_mode.pen);
+                assert!(!draw_mode.fill);
+                assert_eq!(Point::new(14., 22.), poly.points.0[2]);
+            }
+            _ => panic!("expected a closed shape"),
+        }
+        match &shapes[1] {
+            ImportedShape::Closed(poly, draw_mode) => {
+                assert_eq!(Some(2), draw_mode.pen);
+                assert!(draw_mode.fill);
+                assert_eq!(1, poly.holes.len());
+                assert!(poly.points.signed_area() * poly.holes[0].signed_area() < 0.);
+            }
+            _ => panic!("expected a closed shape"),
+        }
+        match &shapes[2] {
+            ImportedShape::Open(polyline, pen) => {
+                assert_eq!(3, polyline.0.len());
+                assert_eq!(3, *pen);
+            }
+            _ => panic!("expected an open shape"),
+        }
+
+        let malformed = r#"<svg><path d="M 0 0 L 1 x"/></svg>"#;
+        assert!(matches!(
+            SVGReader::new().read_str(malformed),
+            Err(Error::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_pens_by_layer() {
+        let shapes = SVGReader::new()
+            .pen_source(PenSource::Layer)
+            .read_str(DOCUMENT)
+            .unwrap();
+        let pens: Vec<usize> = shapes
+            .iter()
+            .map(|s| match s {
+                ImportedShape::Closed(_, d) => d.pen.unwrap(),
+                ImportedShape::Open(_, pen) => *pen,
+            })
+            .collect();
+        assert_eq!(vec![3, 3, 4], pens);
+    }
+
+    #[test]
+    fn test_transform() {
+        let t = parse_transform("translate(1 2) scale(2)").unwrap();
+        assert_eq!(Point::new(3., 4.), t.apply(Point::new(1., 1.)));
+
+        let r = parse_transform("rotate(90, 1, 1)").unwrap();
+        let p = r.apply(Point::new(2., 1.));
+        assert!((p - Point::new(1., 2.)).norm() < 1e-12);
+    }
+}
@@ -1,8 +1,10 @@
 //! Rendering pipeline for pen-plotted 3D graphics.
 
 pub mod draw_mode;
+pub mod error;
 pub mod export;
 pub mod geom;
+pub mod import;
 pub mod noise;
 pub mod optimizer;
 pub mod plot;
@@ -51,10 +51,10 @@ impl NoiseMaker {
 
     fn random(&self, mut x: usize, mut y: usize) -> f64 {
         if let Some(xp) = self.x_period {
-            x = x % xp;
+            x %= xp;
         }
         if let Some(yp) = self.y_period {
-            y = y % yp;
+            y %= yp;
         }
         pseudo_random(x as f64 * self.x_seed + y as f64 * self.y_seed)
     }
@@ -64,7 +64,7 @@ impl NoiseMaker {
     }
 
     fn smooth_step(v1: f64, v2: f64, w: f64) -> f64 {
-        let mut w = w.max(0.).min(1.);
+        let mut w = w.clamp(0., 1.);
         w = 6. * w.powi(5) - 15. * w.powi(4) + 10. * w.powi(3);
         (1. - w) * v1 + w * v2
     }
@@ -0,0 +1,415 @@
+//! Joining the segments of a layer into continuous paths, so that they can
+//! be drawn with fewer pen lifts.
+//!
+//! Joining happens in three steps. First, endpoints that are within a
+//! tolerance of each other are snapped together. Next, segments that lie
+//! along the same line are merged so that no part of the line is drawn
+//! twice; this removes duplicates such as the shared edges of adjacent
+//! faces. Finally, segments that share endpoints are chained into paths.
+
+use crate::geom::line_segment::{snap_endpoints, LineSegment};
+use crate::plot::Layer;
+use crate::types::{Point, Vector};
+use rstar::primitives::PointWithData;
+use rstar::{RTree, RTreeObject, AABB};
+use std::collections::HashMap;
+
+/// A line segment with its index in a list.
+struct IndexedSegment {
+    line: LineSegment,
+    index: usize,
+}
+
+impl RTreeObject for IndexedSegment {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.line.envelope()
+    }
+}
+
+/// Join the segments of a layer into continuous paths, treating endpoints
+/// within `epsilon` of each other as the same point. Paths are ordered
+/// greedily, starting at the given origin.
+pub fn join_paths(layer: Layer, epsilon: f64, origin: Point) -> Layer {
+    let lines = snap_endpoints(&layer.lines, epsilon);
+    let lines = merge_collinear(lines, epsilon);
+    let lines = chain(lines, origin);
+
+    Layer {
+        lines,
+        pen: layer.pen,
+    }
+}
+
+/// Whether two segments lie along the same line, to within `epsilon`.
+fn collinear(a: &LineSegment, b: &LineSegment, epsilon: f64) -> bool {
+    let distance = |line: &LineSegment, p: &Point| {
+        let v = line.vector();
+        let w = p - line.c1;
+        (v.x * w.y - v.y * w.x).abs() / v.norm()
+    };
+    distance(a, &b.c1) <= epsilon
+        && distance(a, &b.c2) <= epsilon
+        && distance(b, &a.c1) <= epsilon
+        && distance(b, &a.c2) <= epsilon
+}
+
+/// Replace each group of collinear segments by the parts of the line that
+/// they cover. Each part takes its pen and object from a segment that covers
+/// it, and consecutive parts with the same pen and object are joined again
+/// unless another segment ends where they meet, so that paths through that
+/// point can still be chained.
+fn merge_collinear(lines: Vec<LineSegment>, epsilon: f64) -> Vec<LineSegment> {
+    let tree = RTree::bulk_load(
+        lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| IndexedSegment { line: *line, index })
+            .collect(),
+    );
+
+    let key = |p: &Point| (p.x.to_bits(), p.y.to_bits());
+    let mut ends: HashMap<(u64, u64), usize> = HashMap::new();
+    for line in &lines {
+        *ends.entry(key(&line.c1)).or_insert(0) += 1;
+        *ends.entry(key(&line.c2)).or_insert(0) += 1;
+    }
+
+    let mut merged = vec![false; lines.len()];
+    let mut result = Vec::new();
+
+    for (i, seed) in lines.iter().enumerate() {
+        if merged[i] {
+            continue;
+        }
+        merged[i] = true;
+
+        let mut group = vec![*seed];
+        let mut queue = vec![*seed];
+        while let Some(member) = queue.pop() {
+            let envelope = member.envelope();
+            let (lower, upper) = (envelope.lower(), envelope.upper());
+            let search = AABB::from_corners(
+                [lower[0] - epsilon, lower[1] - epsilon],
+                [upper[0] + epsilon, upper[1] + epsilon],
+            );
+            for other in tree.locate_in_envelope_intersecting(&search) {
+                if !merged[other.index] && collinear(seed, &other.line, epsilon) {
+                    merged[other.index] = true;
+                    group.push(other.line);
+                    queue.push(other.line);
+                }
+            }
+        }
+
+        if group.len() == 1 {
+            result.push(*seed);
+            continue;
+        }
+
+        let direction = seed.vector().normalize();
+        let position = |p: &Point| (p - seed.c1).dot(&direction);
+        let covered: Vec<(f64, f64, &LineSegment)> = group
+            .iter()
+            .map(|line| {
+                let (a, b) = (position(&line.c1), position(&line.c2));
+                (a.min(b), a.max(b), line)
+            })
+            .collect();
+
+        let mut breaks: Vec<(f64, Point)> = group
+            .iter()
+            .flat_map(|line| vec![line.c1, line.c2])
+            .map(|p| (position(&p), p))
+            .collect();
+        breaks.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        breaks.dedup_by(|b, a| b.0 - a.0 <= epsilon);
+
+        // Whether a segment outside the group ends at a point.
+        let mut group_ends: HashMap<(u64, u64), usize> = HashMap::new();
+        for line in &group {
+            *group_ends.entry(key(&line.c1)).or_insert(0) += 1;
+            *group_ends.entry(key(&line.c2)).or_insert(0) += 1;
+        }
+        let junction = |p: &Point| {
+            ends.get(&key(p)).copied().unwrap_or(0) > group_ends.get(&key(p)).copied().unwrap_or(0)
+        };
+
+        // Each part keeps the pen and object of the first segment covering it.
+        let mut parts: Vec<LineSegment> = Vec::new();
+        for pair in breaks.windows(2) {
+            let middle = (pair[0].0 + pair[1].0) / 2.;
+            if let Some((_, _, line)) = covered
+                .iter()
+                .find(|(a, b, _)| *a <= middle && middle <= *b)
+            {
+                match parts.last_mut() {
+                    Some(last)
+                        if last.c2 == pair[0].1
+                            && last.pen == line.pen
+                            && last.object == line.object
+                            && !junction(&last.c2) =>
+                    {
+                        last.c2 = pair[1].1
+                    }
+                    _ => parts.push(LineSegment {
+                        c1: pair[0].1,
+                        c2: pair[1].1,
+                        ..**line
+                    }),
+                }
+            }
+        }
+        result.extend(parts);
+    }
+
+    result
+}
+
+/// Chain segments into paths, then order the paths.
+fn chain(lines: Vec<LineSegment>, origin: Point) -> Vec<LineSegment> {
+    order_paths(build_paths(&lines), origin)
+}
+
+/// Split segments into as few paths as possible, by walking from endpoint to
+/// endpoint and preferring to continue in a straight line.
+fn build_paths(lines: &[LineSegment]) -> Vec<Vec<LineSegment>> {
+    let key = |p: &Point| (p.x.to_bits(), p.y.to_bits());
+
+    let mut node_index: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut nodes: Vec<Point> = Vec::new();
+    let mut edges: Vec<Vec<usize>> = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        for p in &[line.c1, line.c2] {
+            let node = *node_index.entry(key(p)).or_insert_with(|| {
+                nodes.push(*p);
+                edges.push(Vec::new());
+                nodes.len() - 1
+            });
+            edges[node].push(i);
+        }
+    }
+
+    let mut used = vec![false; lines.len()];
+    let walk = |start: usize, used: &mut [bool]| {
+        let mut path: Vec<LineSegment> = Vec::new();
+        let mut node = start;
+        let mut heading: Option<Vector> = None;
+        loop {
+            let leaving = |e: &usize| {
+                let line = lines[*e];
+                if line.c1 == nodes[node] {
+                    line
+                } else {
+                    line.reverse()
+                }
+            };
+            let straightness = |e: &usize| match heading {
+                Some(h) => leaving(e).vector().normalize().dot(&h),
+                None => 0.,
+            };
+            let next = edges[node]
+                .iter()
+                .filter(|e| !used[**e])
+                .max_by(|a, b| straightness(a).partial_cmp(&straightness(b)).unwrap());
+
+            let e = match next {
+                Some(e) => *e,
+                None => break,
+            };
+            let line = leaving(&e);
+            used[e] = true;
+            heading = Some(line.vector().normalize());
+            node = node_index[&key(&line.c2)];
+            path.push(line);
+        }
+        path
+    };
+
+    // Walking from a node with an odd number of unused segments always ends
+    // at another such node, so these walks give open paths. What is left
+    // afterwards is made of closed loops.
+    let odd: Vec<usize> = (0..nodes.len())
+        .filter(|n| edges[*n].len() % 2 == 1)
+        .collect();
+    let mut paths = Vec::new();
+    for start in odd {
+        if edges[start].iter().filter(|e| !used[**e]).count() % 2 == 1 {
+            paths.push(walk(start, &mut used));
+        }
+    }
+    let mut loops = Vec::new();
+    for start in 0..nodes.len() {
+        loop {
+            let path = walk(start, &mut used);
+            if path.is_empty() {
+                break;
+            }
+            loops.push(path);
+        }
+    }
+
+    // Splice each loop into a path that passes through one of its vertices.
+    let mut path_through: HashMap<(u64, u64), usize> = HashMap::new();
+    for (i, path) in paths.iter().enumerate() {
+        for line in path {
+            path_through.insert(key(&line.c1), i);
+            path_through.insert(key(&line.c2), i);
+        }
+    }
+    for mut lp in loops {
+        let shared = lp
+            .iter()
+            .position(|line| path_through.contains_key(&key(&line.c1)));
+        let i = match shared {
+            Some(j) => {
+                lp.rotate_left(j);
+                let vertex = lp[0].c1;
+                let i = path_through[&key(&vertex)];
+                let at = paths[i]
+                    .iter()
+                    .position(|line| line.c1 == vertex)
+                    .unwrap_or_else(|| paths[i].len());
+                paths[i].splice(at..at, lp.iter().cloned());
+                i
+            }
+            None => {
+                paths.push(lp.clone());
+                paths.len() - 1
+            }
+        };
+        for line in &lp {
+            path_through.insert(key(&line.c1), i);
+        }
+    }
+
+    paths
+}
+
+/// A point where a path can start. The data is the index of the path, and
+/// the index of the segment to start from; an index equal to the length of
+/// an open path means that the path is drawn in reverse.
+type PathStart = PointWithData<(usize, usize), [f64; 2]>;
+
+/// Order paths greedily, starting each at the end nearest to where the last
+/// one finished. Closed paths may start at any of their vertices.
+fn order_paths(paths: Vec<Vec<LineSegment>>, origin: Point) -> Vec<LineSegment> {
+    let entries: Vec<Vec<PathStart>> = paths
+        .iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let first = path.first().unwrap().c1;
+            let last = path.last().unwrap().c2;
+            if first == last {
+                path.iter()
+                    .enumerate()
+                    .map(|(j, line)| PointWithData::new((i, j), [line.c1.x, line.c1.y]))
+                    .collect()
+            } else {
+                vec![
+                    PointWithData::new((i, 0), [first.x, first.y]),
+                    PointWithData::new((i, path.len()), [last.x, last.y]),
+                ]
+            }
+        })
+        .collect();
+    let mut tree = RTree::bulk_load(entries.iter().flatten().cloned().collect());
+
+    let mut result: Vec<LineSegment> = Vec::new();
+    let mut cursor = origin;
+    while let Some(nearest) = tree.nearest_neighbor(&[cursor.x, cursor.y]).cloned() {
+        let (i, start) = nearest.data;
+        for entry in &entries[i] {
+            tree.remove(entry);
+        }
+
+        let path = &paths[i];
+        if start == path.len() {
+            result.extend(path.iter().rev().map(|line| line.reverse()));
+        } else {
+            result.extend(&path[start..]);
+            result.extend(&path[..start]);
+        }
+        cursor = result.last().unwrap().c2;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geom::polygon::Polygon;
+    use crate::scene::Scene;
+    use crate::types::pt;
+
+    fn layer(lines: Vec<LineSegment>) -> Layer {
+        Layer { lines, pen: 0 }
+    }
+
+    #[test]
+    fn test_shared_edge() {
+        let mut lines = Polygon::from_coords(vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)])
+            .points
+            .line_segments();
+        lines.extend(
+            Polygon::from_coords(vec![(1., 1e-9), (2., 0.), (2., 1.), (1., 1.)])
+                .points
+                .line_segments(),
+        );
+        let before = layer(lines.clone()).cost(pt(0., 0.));
+        assert_eq!(8, before.segments);
+
+        let joined = join_paths(layer(lines), 1e-6, pt(0., 0.));
+        let after = joined.cost(pt(0., 0.));
+        assert_eq!(7, after.segments);
+        assert!((after.line_cost - 7.).abs() < 1e-9);
+        assert!(after.moves <= 2);
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let lines = vec![
+            LineSegment::new(pt(0., 0.), pt(2., 0.)),
+            LineSegment::new(pt(3., 0.), pt(1., 0.)),
+        ];
+        let before = layer(lines.clone()).cost(pt(0., 0.));
+        let joined = join_paths(layer(lines), 1e-6, pt(0., 0.));
+        let after = joined.cost(pt(0., 0.));
+
+        // The overlap is drawn once, as a single segment.
+        assert_eq!(2, before.segments);
+        assert_eq!(1, after.segments);
+        assert_eq!(0, after.moves);
+        assert!((after.line_cost - 3.).abs() < 1e-9);
+        assert_eq!(vec![LineSegment::new(pt(0., 0.), pt(3., 0.))], joined.lines);
+
+        // A segment ending inside the overlap keeps the line split there,
+        // so that it can be chained through that point.
+        let lines = vec![
+            LineSegment::new(pt(0., 0.), pt(2., 0.)),
+            LineSegment::new(pt(3., 0.), pt(1., 0.)),
+            LineSegment::new(pt(1., 0.), pt(1., 1.)),
+        ];
+        let joined = join_paths(layer(lines), 1e-6, pt(0., 0.));
+        assert_eq!(3, joined.cost(pt(0., 0.)).segments);
+    }
+
+    #[test]
+    fn test_overlapping_objects() {
+        let mut scene = Scene::new();
+        let first = scene.add_segment(LineSegment::new(pt(0., 0.), pt(2., 0.)));
+        let second = scene.add_segment(LineSegment::new(pt(1., 0.), pt(3., 0.)));
+        let lines = scene.to_plot().unwrap().layers.remove(0).lines;
+
+        let joined = join_paths(layer(lines), 1e-6, pt(0., 0.));
+        let objects: Vec<(f64, f64, _)> = joined
+            .lines
+            .iter()
+            .map(|line| (line.c1.x, line.c2.x, line.object))
+            .collect();
+        let expected = vec![(0., 2., Some(first)), (2., 3., Some(second))];
+        assert_eq!(expected, objects);
+    }
+}
@@ -1,21 +1,35 @@
 //! Methods of optimizing drawing order for a pen plot.
 
+pub mod join;
+pub mod two_opt;
+
 use crate::geom::line_segment::LineSegment;
+use crate::optimizer::two_opt::Budget;
 use crate::plot::Layer;
 use crate::types::Point;
 use rstar::{PointDistance, RTree, RTreeObject, AABB};
 
+/// Strategy used to order the segments of a plot.
+#[derive(Clone, Copy, Debug)]
+pub enum Strategy {
+    /// Nearest-neighbour ordering.
+    Greedy,
+    /// Nearest-neighbour ordering, refined with 2-opt moves until the budget
+    /// is used up.
+    TwoOpt { budget: Budget },
+}
+
+/// A line segment in one of the two directions it can be drawn in, indexed
+/// by its starting point.
 #[derive(PartialEq, Clone)]
 struct TreeElement {
-    start: Point,
-    end: Point,
+    line: LineSegment,
 }
 
 impl TreeElement {
     pub fn swap(&self) -> TreeElement {
         TreeElement {
-            start: self.end,
-            end: self.start,
+            line: self.line.reverse(),
         }
     }
 }
@@ -24,13 +38,14 @@ impl RTreeObject for TreeElement {
     type Envelope = AABB<[f64; 2]>;
 
     fn envelope(&self) -> Self::Envelope {
-        AABB::from_corners([self.start.x, self.start.y], [self.start.x, self.start.y])
+        let start = self.line.c1;
+        AABB::from_corners([start.x, start.y], [start.x, start.y])
     }
 }
 
 impl PointDistance for TreeElement {
     fn distance_2(&self, point: &[f64; 2]) -> f64 {
-        (self.start - Point::from(*point)).norm()
+        (self.line.c1 - Point::from(*point)).norm()
     }
 }
 
@@ -41,16 +56,9 @@ pub fn greedy_optimize(layer: Layer, origin: Point) -> Layer {
         .lines
         .iter()
         .flat_map(|d| {
-            vec![
-                TreeElement {
-                    start: d.c1,
-                    end: d.c2,
-                },
-                TreeElement {
-                    start: d.c2,
-                    end: d.c1,
-                },
-            ]
+            let element = TreeElement { line: *d };
+            let swapped = element.swap();
+            vec![element, swapped]
         })
         .collect();
 
@@ -61,8 +69,8 @@ pub fn greedy_optimize(layer: Layer, origin: Point) -> Layer {
     while tree.size() > 0 {
         let next = (*tree.nearest_neighbor(&[cursor.x, cursor.y]).unwrap()).clone();
 
-        lines.push(LineSegment::new(next.start, next.end));
-        cursor = next.end;
+        lines.push(next.line);
+        cursor = next.line.c2;
 
         tree.remove(&next);
         tree.remove(&next.swap());
@@ -73,3 +81,29 @@ pub fn greedy_optimize(layer: Layer, origin: Point) -> Layer {
         pen: layer.pen,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pt;
+
+    #[test]
+    fn test_greedy_keeps_pens() {
+        let layer = Layer {
+            lines: vec![
+                LineSegment::new_with_pen(pt(3., 0.), pt(2., 0.), 1),
+                LineSegment::new_with_pen(pt(0., 0.), pt(1., 0.), 2),
+            ],
+            pen: 0,
+        };
+        let result = greedy_optimize(layer, pt(0., 0.));
+
+        assert_eq!(
+            vec![
+                LineSegment::new_with_pen(pt(0., 0.), pt(1., 0.), 2),
+                LineSegment::new_with_pen(pt(2., 0.), pt(3., 0.), 1),
+            ],
+            result.lines
+        );
+    }
+}
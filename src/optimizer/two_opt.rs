@@ -0,0 +1,197 @@
+//! Refinement of drawing order with 2-opt moves.
+//!
+//! The layer is split into paths (runs of segments that are drawn without
+//! lifting the pen), which are kept intact. A 2-opt move reverses the order
+//! (and direction) of a contiguous run of paths, which replaces two pen-up
+//! moves with two others; a move is made whenever it shortens the total
+//! pen-up distance. Reversing a single path is a special case of this.
+
+use crate::geom::line_segment::LineSegment;
+use crate::plot::Layer;
+use crate::types::Point;
+use rstar::primitives::PointWithData;
+use rstar::RTree;
+use std::time::{Duration, Instant};
+
+/// The number of nearby paths considered as candidates for each move.
+const NEIGHBORS: usize = 10;
+
+/// A limit on how long an optimizer may run.
+#[derive(Clone, Copy, Debug)]
+pub enum Budget {
+    /// Stop after the given number of passes over the plot.
+    Iterations(usize),
+    /// Stop after the given amount of time has elapsed.
+    Time(Duration),
+}
+
+/// Improve the drawing order of a layer with 2-opt moves, starting and
+/// ending at the given origin. Stops when the budget is used up or no move
+/// improves the order.
+pub fn two_opt_optimize(layer: Layer, origin: Point, budget: Budget) -> Layer {
+    let paths = split_paths(&layer.lines);
+    let n = paths.len();
+    if n < 2 {
+        return layer;
+    }
+
+    let start = |(path, reversed): (usize, bool)| -> Point {
+        let lines: &Vec<LineSegment> = &paths[path];
+        if reversed {
+            lines.last().unwrap().c2
+        } else {
+            lines[0].c1
+        }
+    };
+    let end = |(path, reversed): (usize, bool)| -> Point {
+        let lines: &Vec<LineSegment> = &paths[path];
+        if reversed {
+            lines[0].c1
+        } else {
+            lines.last().unwrap().c2
+        }
+    };
+    let distance = |a: Point, b: Point| (b - a).norm();
+
+    let tree = RTree::bulk_load(
+        paths
+            .iter()
+            .enumerate()
+            .flat_map(|(i, lines)| {
+                let (a, b) = (lines[0].c1, lines.last().unwrap().c2);
+                vec![
+                    PointWithData::new(i, [a.x, a.y]),
+                    PointWithData::new(i, [b.x, b.y]),
+                ]
+            })
+            .collect(),
+    );
+
+    let mut order: Vec<(usize, bool)> = (0..n).map(|i| (i, false)).collect();
+    let mut position: Vec<usize> = (0..n).collect();
+    let deadline = match budget {
+        Budget::Time(duration) => Some(Instant::now() + duration),
+        Budget::Iterations(_) => None,
+    };
+    let out_of_time = || deadline.is_some_and(|d| Instant::now() >= d);
+
+    let mut passes = 0;
+    'passes: loop {
+        if let Budget::Iterations(max) = budget {
+            if passes >= max {
+                break;
+            }
+        }
+        passes += 1;
+
+        let mut improved = false;
+        for i in 0..n {
+            if out_of_time() {
+                break 'passes;
+            }
+
+            // Consider replacing the move from `a` to `b` (into position `i`)
+            // and the move from `c` to `d` (out of position `j`) by moves from
+            // `a` to `c` and from `b` to `d`, reversing everything in between.
+            let a = if i == 0 { origin } else { end(order[i - 1]) };
+            let b = start(order[i]);
+            let candidates: Vec<usize> = tree
+                .nearest_neighbor_iter(&[a.x, a.y])
+                .take(NEIGHBORS)
+                .map(|entry| position[entry.data])
+                .filter(|j| *j >= i)
+                .collect();
+
+            for j in candidates {
+                let c = end(order[j]);
+                let d = if j + 1 == n {
+                    origin
+                } else {
+                    start(order[j + 1])
+                };
+
+                let delta = distance(a, c) + distance(b, d) - distance(a, b) - distance(c, d);
+                if delta < -1e-9 {
+                    order[i..=j].reverse();
+                    for (k, entry) in order.iter_mut().enumerate().take(j + 1).skip(i) {
+                        entry.1 = !entry.1;
+                        position[entry.0] = k;
+                    }
+                    improved = true;
+                    break;
+                }
+            }
+        }
+
+        if !improved {
+            break;
+        }
+    }
+
+    let mut lines = Vec::with_capacity(layer.lines.len());
+    for (path, reversed) in order {
+        if reversed {
+            lines.extend(paths[path].iter().rev().map(LineSegment::reverse));
+        } else {
+            lines.extend(&paths[path]);
+        }
+    }
+
+    Layer {
+        lines,
+        pen: layer.pen,
+    }
+}
+
+/// Split a list of segments into runs that are drawn without lifting the pen.
+fn split_paths(lines: &[LineSegment]) -> Vec<Vec<LineSegment>> {
+    let mut paths: Vec<Vec<LineSegment>> = Vec::new();
+    for line in lines {
+        match paths.last_mut() {
+            Some(path) if path.last().unwrap().c2 == line.c1 => path.push(*line),
+            _ => paths.push(vec![*line]),
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::pt;
+
+    #[test]
+    fn test_uncross() {
+        // Vertical strokes visited out of order, and some drawn in the
+        // opposite direction to their neighbours.
+        let stroke = |x: f64, up: bool| {
+            if up {
+                LineSegment::new(pt(x, 0.), pt(x, 1.))
+            } else {
+                LineSegment::new(pt(x, 1.), pt(x, 0.))
+            }
+        };
+        let layer = Layer {
+            lines: vec![
+                stroke(0., true),
+                stroke(3., true),
+                stroke(2., false),
+                stroke(1., true),
+                stroke(4., false),
+            ],
+            pen: 0,
+        };
+        let origin = pt(0., 0.);
+        let before = layer.cost(origin);
+
+        let result = two_opt_optimize(layer, origin, Budget::Iterations(10));
+        let after = result.cost(origin);
+
+        assert_eq!(5, after.segments);
+        assert!((after.line_cost - before.line_cost).abs() < 1e-9);
+        // One unit across between alternating strokes, then back to the origin.
+        assert_eq!(4, after.moves);
+        assert!((after.move_cost - (4. + 17f64.sqrt())).abs() < 1e-9);
+        assert!(after.move_cost < before.move_cost);
+    }
+}
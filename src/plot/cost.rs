@@ -1,5 +1,5 @@
 use std::iter::Sum;
-use std::ops::AddAssign;
+use std::ops::{AddAssign, Sub};
 
 /// Represents the cost of a plot.
 /// `move_cost` and `moves` are dependent on drawing order of the plot;
@@ -25,6 +25,36 @@ impl AddAssign for PlotCost {
     }
 }
 
+/// The difference between the costs of two plots, as the savings of the
+/// second plot over the first. Every field is signed, so a plot that costs
+/// more than the one it is compared to gives negative savings.
+#[derive(Debug, Default)]
+pub struct PlotSavings {
+    /// The reduction in pen-down distance.
+    pub move_cost: f64,
+    /// The reduction in pen-up distance.
+    pub line_cost: f64,
+    /// The reduction in the number of `LineSegments`.
+    pub segments: i64,
+    /// The reduction in the number of pen-up moves.
+    pub moves: i64,
+}
+
+/// Subtracting the cost of one plot from another gives the savings of the
+/// second plot over the first.
+impl Sub for PlotCost {
+    type Output = PlotSavings;
+
+    fn sub(self, rhs: PlotCost) -> PlotSavings {
+        PlotSavings {
+            move_cost: self.move_cost - rhs.move_cost,
+            line_cost: self.line_cost - rhs.line_cost,
+            segments: self.segments as i64 - rhs.segments as i64,
+            moves: self.moves as i64 - rhs.moves as i64,
+        }
+    }
+}
+
 impl Sum for PlotCost {
     fn sum<I: Iterator<Item = PlotCost>>(iter: I) -> Self {
         let mut cost: PlotCost = Default::default();
@@ -34,3 +64,22 @@ impl Sum for PlotCost {
         cost
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_savings_can_be_negative() {
+        let cost = |segments, moves| PlotCost {
+            move_cost: 1.,
+            line_cost: 2.,
+            segments,
+            moves,
+        };
+        let savings = cost(3, 1) - cost(4, 0);
+        assert_eq!(-1, savings.segments);
+        assert_eq!(1, savings.moves);
+        assert_eq!(0., savings.line_cost);
+    }
+}
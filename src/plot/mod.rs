@@ -3,7 +3,9 @@
 pub mod cost;
 
 use crate::geom::line_segment::LineSegment;
-use crate::optimizer::greedy_optimize;
+use crate::optimizer::join::join_paths;
+use crate::optimizer::two_opt::two_opt_optimize;
+use crate::optimizer::{greedy_optimize, Strategy};
 use crate::plot::cost::PlotCost;
 use crate::types::Point;
 
@@ -80,12 +82,24 @@ impl Plot {
     }
 
     /// Apply greedy optimization to the plot.
-    pub fn optimize(mut self) -> Plot {
+    pub fn optimize(self) -> Plot {
+        self.optimize_with(Strategy::Greedy)
+    }
+
+    /// Optimize the drawing order of the plot with the given strategy.
+    pub fn optimize_with(mut self, strategy: Strategy) -> Plot {
         let mut v = Vec::new();
         std::mem::swap(&mut self.layers, &mut v);
+        let origin = self.origin;
         v = v
             .into_iter()
-            .map(|l| greedy_optimize(l, self.origin))
+            .map(|l| {
+                let l = greedy_optimize(l, origin);
+                match strategy {
+                    Strategy::Greedy => l,
+                    Strategy::TwoOpt { budget } => two_opt_optimize(l, origin, budget),
+                }
+            })
             .collect();
 
         Plot {
@@ -95,4 +109,17 @@ impl Plot {
             layers: v,
         }
     }
+
+    /// Join line segments into continuous paths, treating endpoints within
+    /// `epsilon` of each other as the same point. This also removes segments
+    /// that are drawn more than once, and orders the paths greedily.
+    pub fn join_paths(mut self, epsilon: f64) -> Plot {
+        let origin = self.origin;
+        self.layers = self
+            .layers
+            .into_iter()
+            .map(|l| join_paths(l, epsilon, origin))
+            .collect();
+        self
+    }
 }
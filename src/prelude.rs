@@ -3,18 +3,34 @@
 pub use std::f64::consts::PI;
 pub const TWO_PI: f64 = PI * 2.;
 
-pub use crate::draw_mode::{fill_only, pen, stroke};
-pub use crate::export::WriteSVG;
+pub use crate::draw_mode::{fill_only, pen, stroke, PenMask};
+pub use crate::error::Error;
+pub use crate::export::gcode::WriteGCode;
+pub use crate::export::hpgl::WriteHPGL;
+pub use crate::export::{Align, Margins, Paper, Pen, Scale, WriteSVG};
+pub use crate::geom::hatch::Hatch;
 pub use crate::geom::line_segment::LineSegment;
+pub use crate::geom::offset::Join;
+pub use crate::geom::path::PathBuilder;
 pub use crate::geom::polygon::Polygon;
-pub use crate::noise::{NoiseMaker, pseudo_random};
+pub use crate::geom::polyline::Polyline;
+pub use crate::import::obj::ObjReader;
+pub use crate::import::stl::read_stl;
+pub use crate::import::svg::SVGReader;
+pub use crate::noise::{pseudo_random, NoiseMaker};
+pub use crate::optimizer::two_opt::Budget;
+pub use crate::optimizer::Strategy;
 pub use crate::plot::Plot;
 pub use crate::projection::apply::{Apply, ApplyOps};
+pub use crate::projection::camera::Camera;
 pub use crate::projection::form::Form;
-pub use crate::projection::platonic::{cube, tetrahedron};
+pub use crate::projection::light::{Light, Shading};
+pub use crate::projection::parallel::Projection;
+pub use crate::projection::parametric::{capsule, cone, cylinder, icosphere, sphere, torus};
+pub use crate::projection::platonic::{cube, dodecahedron, icosahedron, octahedron, tetrahedron};
 pub use crate::projection::polygon3::Polygon3;
-pub use crate::projection::scene3::Scene3;
+pub use crate::projection::scene3::{DepthSort, Edges, Scene3};
 pub use crate::projection::types3::{pt3, vec3};
 pub use crate::scene::Scene;
 pub use crate::shape::{circle, square};
-pub use crate::types::{pt, vec, Point, PointActions, Vector, VectorExtension};
+pub use crate::types::{pt, vec, ObjectId, Point, PointActions, Vector, VectorExtension};
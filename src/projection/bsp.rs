@@ -0,0 +1,376 @@
+//! Exact back-to-front ordering of 3D polygons using a binary space
+//! partitioning (BSP) tree. Polygons that straddle a partitioning plane
+//! are split in two; the edges created by splitting are never stroked,
+//! so the drawn outlines are the same as those of the original polygons.
+
+use crate::projection::polygon3::{Face, Polygon3};
+use crate::projection::types3::{Point3, Vector3};
+
+/// The number of candidate polygons to consider when choosing each
+/// partitioning plane.
+const PLANE_CANDIDATES: usize = 8;
+
+/// The position of the viewer, in the coordinates of the faces.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Eye {
+    /// A viewer at a finite point, as in a perspective projection.
+    Point(Point3),
+    /// A viewer infinitely far away in the given direction, as in an
+    /// orthographic projection.
+    Direction(Vector3),
+}
+
+impl Eye {
+    /// The direction from a point towards the viewer.
+    pub(crate) fn view(&self, point: &Point3) -> Vector3 {
+        match self {
+            Eye::Point(p) => p - point,
+            Eye::Direction(v) => *v,
+        }
+    }
+}
+
+/// A plane, with points in front of it where `distance` is positive.
+#[derive(Clone, Copy)]
+pub(crate) struct Plane {
+    normal: Vector3,
+    offset: f64,
+}
+
+impl Plane {
+    /// Construct the plane of points `p` with `normal · p + offset = 0`,
+    /// given a unit normal.
+    pub(crate) fn new(normal: Vector3, offset: f64) -> Plane {
+        Plane { normal, offset }
+    }
+
+    fn from_poly(poly: &Polygon3) -> Option<Plane> {
+        let normal = poly.normal();
+        let norm = normal.norm();
+        if norm == 0. || !norm.is_finite() {
+            return None;
+        }
+        let normal = normal / norm;
+        Some(Plane {
+            normal,
+            offset: -normal.dot(&poly.points[0].coords),
+        })
+    }
+
+    fn distance(&self, point: &Point3) -> f64 {
+        self.normal.dot(&point.coords) + self.offset
+    }
+
+    fn eye_side(&self, eye: &Eye) -> f64 {
+        match eye {
+            Eye::Point(p) => self.distance(p),
+            Eye::Direction(v) => self.normal.dot(v),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Front,
+    Back,
+    On,
+}
+
+enum Classification {
+    Front,
+    Back,
+    Coplanar,
+    Spanning,
+}
+
+/// A set of faces still to be partitioned, and the parent node and side
+/// that the resulting node belongs to.
+type Work = (Vec<Face>, Option<(usize, bool)>);
+
+struct Node {
+    plane: Plane,
+    faces: Vec<Face>,
+    front: Option<usize>,
+    back: Option<usize>,
+}
+
+/// Order `faces` from back to front as seen from `eye`, splitting faces
+/// where no order exists.
+pub(crate) fn bsp_order(faces: Vec<Face>, eye: Eye) -> Vec<Face> {
+    let epsilon = epsilon(&faces);
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut degenerate: Vec<Face> = Vec::new();
+
+    // Build the tree without recursion, since scenes can be large.
+    let mut work: Vec<Work> = vec![(faces, None)];
+    while let Some((faces, parent)) = work.pop() {
+        let plane = match choose_plane(&faces, epsilon) {
+            Some(plane) => plane,
+            None => {
+                degenerate.extend(faces);
+                continue;
+            }
+        };
+
+        let mut node = Node {
+            plane,
+            faces: Vec::new(),
+            front: None,
+            back: None,
+        };
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+
+        for face in faces {
+            match classify(&plane, &face.poly, epsilon) {
+                Classification::Front => front.push(face),
+                Classification::Back => back.push(face),
+                Classification::Coplanar => node.faces.push(face),
+                Classification::Spanning => {
+                    let (f, b) = split(&plane, &face, epsilon);
+                    front.extend(f);
+                    back.extend(b);
+                }
+            }
+        }
+
+        let index = nodes.len();
+        nodes.push(node);
+        if let Some((parent, is_front)) = parent {
+            if is_front {
+                nodes[parent].front = Some(index);
+            } else {
+                nodes[parent].back = Some(index);
+            }
+        }
+        if !front.is_empty() {
+            work.push((front, Some((index, true))));
+        }
+        if !back.is_empty() {
+            work.push((back, Some((index, false))));
+        }
+    }
+
+    // Traverse the tree, visiting the subtree on the far side of each plane first.
+    enum Step {
+        Visit(usize),
+        Emit(usize),
+    }
+    let mut result = degenerate;
+    let mut steps = Vec::new();
+    if !nodes.is_empty() {
+        steps.push(Step::Visit(0));
+    }
+    while let Some(step) = steps.pop() {
+        match step {
+            Step::Visit(i) => {
+                let node = &nodes[i];
+                let (near, far) = if node.plane.eye_side(&eye) >= 0. {
+                    (node.front, node.back)
+                } else {
+                    (node.back, node.front)
+                };
+                // Pushed in reverse order of traversal.
+                if let Some(near) = near {
+                    steps.push(Step::Visit(near));
+                }
+                steps.push(Step::Emit(i));
+                if let Some(far) = far {
+                    steps.push(Step::Visit(far));
+                }
+            }
+            Step::Emit(i) => result.append(&mut nodes[i].faces),
+        }
+    }
+
+    result
+}
+
+/// Clip a face to the part in front of a plane. Edges along the plane are
+/// not stroked.
+pub(crate) fn clip(plane: &Plane, face: &Face) -> Option<Face> {
+    let epsilon = epsilon(std::slice::from_ref(face));
+    match classify(plane, &face.poly, epsilon) {
+        Classification::Front | Classification::Coplanar => Some(face.clone()),
+        Classification::Back => None,
+        Classification::Spanning => split(plane, face, epsilon).0,
+    }
+}
+
+/// Compute a tolerance for classifying points as lying on a plane, relative
+/// to the extent of the scene.
+fn epsilon(faces: &[Face]) -> f64 {
+    let extent = faces
+        .iter()
+        .flat_map(|f| f.poly.points.iter())
+        .map(|p| p.coords.amax())
+        .fold(0., f64::max);
+    (extent * 1e-9).max(1e-12)
+}
+
+/// Choose the plane of one of the first few faces, preferring the one that
+/// splits the fewest other faces.
+fn choose_plane(faces: &[Face], epsilon: f64) -> Option<Plane> {
+    faces
+        .iter()
+        .filter_map(|f| Plane::from_poly(&f.poly))
+        .take(PLANE_CANDIDATES)
+        .min_by_key(|plane| {
+            faces
+                .iter()
+                .filter(|f| matches!(classify(plane, &f.poly, epsilon), Classification::Spanning))
+                .count()
+        })
+}
+
+fn side(plane: &Plane, point: &Point3, epsilon: f64) -> Side {
+    let d = plane.distance(point);
+    if d > epsilon {
+        Side::Front
+    } else if d < -epsilon {
+        Side::Back
+    } else {
+        Side::On
+    }
+}
+
+fn classify(plane: &Plane, poly: &Polygon3, epsilon: f64) -> Classification {
+    let mut front = false;
+    let mut back = false;
+    for p in &poly.points {
+        match side(plane, p, epsilon) {
+            Side::Front => front = true,
+            Side::Back => back = true,
+            Side::On => (),
+        }
+    }
+    match (front, back) {
+        (true, true) => Classification::Spanning,
+        (true, false) => Classification::Front,
+        (false, true) => Classification::Back,
+        (false, false) => Classification::Coplanar,
+    }
+}
+
+/// Split a face into the parts in front of and behind a plane. Edges along
+/// the plane are not stroked.
+fn split(plane: &Plane, face: &Face, epsilon: f64) -> (Option<Face>, Option<Face>) {
+    let points = &face.poly.points;
+    let n = points.len();
+    let sides: Vec<Side> = points.iter().map(|p| side(plane, p, epsilon)).collect();
+
+    // Each output vertex records the original edge along which it was
+    // reached, if any; the edge leading into a vertex is stroked only if
+    // it lies along a stroked original edge.
+    let mut front: Vec<(Point3, Option<usize>)> = Vec::new();
+    let mut back: Vec<(Point3, Option<usize>)> = Vec::new();
+
+    for i in 0..n {
+        let j = (i + 1) % n;
+        let prev = (i + n - 1) % n;
+        let (a, b) = (points[i], points[j]);
+
+        match sides[i] {
+            Side::Front => front.push((a, Some(prev))),
+            Side::Back => back.push((a, Some(prev))),
+            Side::On => {
+                let arrival = |s: Side| {
+                    if sides[prev] == s || sides[prev] == Side::On {
+                        Some(prev)
+                    } else {
+                        None
+                    }
+                };
+                front.push((a, arrival(Side::Front)));
+                back.push((a, arrival(Side::Back)));
+            }
+        }
+
+        let crossing = matches!(
+            (sides[i], sides[j]),
+            (Side::Front, Side::Back) | (Side::Back, Side::Front)
+        );
+        if crossing {
+            let da = plane.distance(&a);
+            let db = plane.distance(&b);
+            let p = a + (b - a) * (da / (da - db));
+            if sides[i] == Side::Front {
+                front.push((p, Some(i)));
+                back.push((p, None));
+            } else {
+                back.push((p, Some(i)));
+                front.push((p, None));
+            }
+        }
+    }
+
+    let make_face = |vertices: Vec<(Point3, Option<usize>)>| {
+        if vertices.len() < 3 {
+            return None;
+        }
+        let m = vertices.len();
+        let edges = (0..m)
+            .map(|k| match vertices[(k + 1) % m].1 {
+                Some(e) => face.edges[e],
+                None => false,
+            })
+            .collect();
+        let points: Vec<Point3> = vertices.into_iter().map(|v| v.0).collect();
+        let center = Point3::from(
+            points
+                .iter()
+                .fold(Vector3::zeros(), |acc, p| acc + p.coords)
+                / m as f64,
+        );
+        Some(Face {
+            poly: Polygon3::new(points, center),
+            draw_mode: face.draw_mode.clone(),
+            edges,
+        })
+    };
+
+    (make_face(front), make_face(back))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projection::types3::pt3;
+
+    fn square(points: Vec<Point3>) -> Face {
+        Face::new(Polygon3::new(points, pt3(0., 0., 0.)), Default::default())
+    }
+
+    #[test]
+    fn test_split_intersecting_faces() {
+        // A square in the plane z = 0, and one tilted through it, which
+        // sorting by center cannot order.
+        let flat = square(vec![
+            pt3(-1., -1., 0.),
+            pt3(1., -1., 0.),
+            pt3(1., 1., 0.),
+            pt3(-1., 1., 0.),
+        ]);
+        let tilted = square(vec![
+            pt3(-1., -1., -1.),
+            pt3(1., -1., -1.),
+            pt3(1., 1., 1.),
+            pt3(-1., 1., 1.),
+        ]);
+
+        let result = bsp_order(vec![flat, tilted], Eye::Direction(Vector3::z()));
+        assert_eq!(3, result.len());
+
+        // The lower half of the tilted square is drawn first, then the flat
+        // square, then the upper half of the tilted square.
+        assert!(result[0].poly.center.z < 0.);
+        assert_eq!(0., result[1].poly.center.z);
+        assert!(result[2].poly.center.z > 0.);
+
+        // The split edge along y = 0 is not stroked.
+        for face in &[&result[0], &result[2]] {
+            let stroked = face.edges.iter().filter(|e| **e).count();
+            assert_eq!(3, stroked);
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! A perspective camera for viewing 3D scenes.
+
+use crate::geom::polygon::Polygon;
+use crate::projection::bsp::{clip, Plane};
+use crate::projection::polygon3::{Face, Polygon3};
+use crate::projection::types3::{Point3, Vector3};
+use crate::types::Point;
+use nalgebra::{Isometry3, Perspective3};
+use std::f64::consts::PI;
+
+/// A camera that views a scene in perspective from a point.
+///
+/// In view space the camera sits at the origin looking down the negative
+/// z axis. Geometry nearer than the near plane or further than the far
+/// plane is clipped away, and the 2D result is cropped to the field of
+/// view.
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    /// The position of the camera.
+    pub eye: Point3,
+    /// The point the camera looks at.
+    pub target: Point3,
+    /// The direction that appears upwards in the image.
+    pub up: Vector3,
+    /// The vertical field of view, in radians.
+    pub fov: f64,
+    /// The ratio of the width of the image to its height.
+    pub aspect: f64,
+    /// The distance from the camera to the near clipping plane.
+    pub near: f64,
+    /// The distance from the camera to the far clipping plane.
+    pub far: f64,
+}
+
+impl Camera {
+    /// Construct a camera at `eye` looking at `target`, with the z axis
+    /// up, a 45° square field of view, and clipping planes at 0.1 and 1000.
+    pub fn new(eye: Point3, target: Point3) -> Camera {
+        Camera {
+            eye,
+            target,
+            up: Vector3::z(),
+            fov: PI / 4.,
+            aspect: 1.,
+            near: 0.1,
+            far: 1000.,
+        }
+    }
+
+    /// Set the direction that appears upwards in the image.
+    pub fn up(mut self, up: Vector3) -> Camera {
+        self.up = up;
+        self
+    }
+
+    /// Set the vertical field of view, in radians.
+    pub fn fov(mut self, fov: f64) -> Camera {
+        self.fov = fov;
+        self
+    }
+
+    /// Set the ratio of the width of the image to its height.
+    pub fn aspect(mut self, aspect: f64) -> Camera {
+        self.aspect = aspect;
+        self
+    }
+
+    /// Set the distances from the camera to the near and far clipping planes.
+    pub fn clip_planes(mut self, near: f64, far: f64) -> Camera {
+        self.near = near;
+        self.far = far;
+        self
+    }
+
+    /// The transformation from world space to view space.
+    pub fn view(&self) -> Isometry3<f64> {
+        Isometry3::look_at_rh(&self.eye, &self.target, &self.up)
+    }
+
+    /// The perspective projection from view space.
+    pub fn perspective(&self) -> Perspective3<f64> {
+        Perspective3::new(self.aspect, self.fov, self.near, self.far)
+    }
+
+    /// Project a point in world space to the image. The image spans
+    /// `-aspect..aspect` horizontally and `-1..1` vertically, with y
+    /// increasing downwards.
+    pub fn project_point(&self, point: Point3) -> Point {
+        self.project_view_point(self.view() * point)
+    }
+
+    /// The corners of the image.
+    pub(crate) fn window(&self) -> (Point, Point) {
+        (Point::new(-self.aspect, -1.), Point::new(self.aspect, 1.))
+    }
+
+    /// Project a polygon in view space to the image.
+    pub(crate) fn project_view_poly(&self, poly: &Polygon3) -> Polygon {
+        Polygon::new(
+            poly.points
+                .iter()
+                .map(|p| self.project_view_point(*p))
+                .collect(),
+        )
+    }
+
+    /// Clip a face in view space to the part between the near and far planes.
+    pub(crate) fn clip(&self, face: &Face) -> Option<Face> {
+        let near = Plane::new(-Vector3::z(), -self.near);
+        let far = Plane::new(Vector3::z(), self.far);
+        clip(&near, face).and_then(|face| clip(&far, &face))
+    }
+
+    fn project_view_point(&self, point: Point3) -> Point {
+        let p = self.perspective().project_point(&point);
+        Point::new(p.x * self.aspect, -p.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projection::apply::Apply;
+    use crate::projection::types3::pt3;
+
+    #[test]
+    fn test_project_and_clip() {
+        let camera = Camera::new(pt3(0., -10., 0.), pt3(0., 0., 0.));
+        assert!((camera.project_point(pt3(0., 0., 0.)) - Point::new(0., 0.)).norm() < 1e-9);
+        // Up in the world is up in the image, which is -y on the page.
+        assert!(camera.project_point(pt3(0., 0., 1.)).y < 0.);
+
+        // A square reaching from in front of the camera to behind it is cut
+        // at the near plane, and the cut edge is not stroked.
+        let square = Polygon3::new(
+            vec![
+                pt3(-1., -20., -1.),
+                pt3(1., -20., -1.),
+                pt3(1., 0., -1.),
+                pt3(-1., 0., -1.),
+            ],
+            pt3(0., -10., -1.),
+        );
+        let face = Face::new(square, Default::default());
+        let viewed = Face {
+            poly: face.poly.clone().apply(&camera.view()),
+            ..face
+        };
+        let clipped = camera.clip(&viewed).unwrap();
+        assert!(clipped
+            .poly
+            .points
+            .iter()
+            .all(|p| p.z <= -camera.near + 1e-9));
+        assert_eq!(3, clipped.edges.iter().filter(|e| **e).count());
+    }
+}
@@ -11,7 +11,7 @@ pub fn isometric_projection() -> Rotation3<f64> {
     // Rotate around x axis, i.e. face top corner of cube.
     // This is a rotation by the Magic Angle.
     // https://en.wikipedia.org/wiki/Magic_angle
-    let angle = (1. as f64 / (3. as f64).sqrt()).acos();
+    let angle = (1. / 3_f64.sqrt()).acos();
     let r2 = Rotation3::from_scaled_axis(Vector3::x() * -angle);
 
     r2 * r1
@@ -0,0 +1,162 @@
+//! Light sources, used to shade the faces of a 3D scene with hatching.
+
+use crate::draw_mode::DrawMode;
+use crate::geom::hatch::Hatch;
+use crate::projection::apply::Apply;
+use crate::projection::bsp::Eye;
+use crate::projection::polygon3::Polygon3;
+use crate::projection::transform::Transform;
+use crate::projection::types3::{Point3, Vector3};
+
+/// A source of light.
+#[derive(Clone, Copy, Debug)]
+pub enum Light {
+    /// Light from infinitely far away. The vector points from the scene
+    /// towards the light.
+    Directional(Vector3),
+    /// Light from a single point.
+    Point(Point3),
+}
+
+impl Light {
+    /// Compute the Lambertian brightness of a polygon, from 0 (unlit) to 1
+    /// (facing the light directly). The side of the polygon facing the viewer
+    /// is the one that is lit, so the winding of the polygon does not matter.
+    pub(crate) fn brightness(&self, poly: &Polygon3, eye: &Eye) -> f64 {
+        let normal = poly.normal();
+        let norm = normal.norm();
+        if norm == 0. {
+            return 0.;
+        }
+
+        let normal = if normal.dot(&eye.view(&poly.center)) < 0. {
+            -normal / norm
+        } else {
+            normal / norm
+        };
+
+        let to_light = match self {
+            Light::Directional(v) => *v,
+            Light::Point(p) => p - poly.center,
+        };
+        let distance = to_light.norm();
+        if distance == 0. {
+            return 1.;
+        }
+
+        (normal.dot(&to_light) / distance).max(0.)
+    }
+}
+
+impl Apply for Light {
+    fn apply(self, transform: &dyn Transform) -> Light {
+        match self {
+            Light::Directional(v) => {
+                let origin = Point3::origin();
+                Light::Directional(
+                    transform.transform_point(origin + v) - transform.transform_point(origin),
+                )
+            }
+            Light::Point(p) => Light::Point(transform.transform_point(p)),
+        }
+    }
+}
+
+/// Controls how the brightness of a face is turned into hatching. Darker
+/// faces are hatched with more closely spaced lines; the spacing is
+/// inversely proportional to the darkness of the face.
+#[derive(Clone, Copy, Debug)]
+pub struct Shading {
+    /// The pen to hatch with.
+    pub pen: usize,
+    /// The angle of the hatch lines, in radians.
+    pub angle: f64,
+    /// The spacing of the hatch lines on a completely dark face.
+    pub min_spacing: f64,
+    /// Faces that would be hatched more sparsely than this are left blank.
+    pub max_spacing: f64,
+    /// Brightness added to every face, from 0 to 1.
+    pub ambient: f64,
+}
+
+impl Default for Shading {
+    fn default() -> Self {
+        Shading {
+            pen: 0,
+            angle: std::f64::consts::FRAC_PI_4,
+            min_spacing: 0.1,
+            max_spacing: 1.,
+            ambient: 0.1,
+        }
+    }
+}
+
+impl Shading {
+    /// Compute the hatching for a face of the given Lambertian brightness.
+    pub fn hatch(&self, brightness: f64) -> Option<(Hatch, usize)> {
+        let light = self.ambient + (1. - self.ambient) * brightness;
+        let darkness = 1. - light;
+        if darkness <= 0. {
+            return None;
+        }
+
+        let spacing = self.min_spacing / darkness;
+        if spacing > self.max_spacing {
+            return None;
+        }
+
+        Some((
+            Hatch::Parallel {
+                angle: self.angle,
+                spacing,
+            },
+            self.pen,
+        ))
+    }
+
+    /// Add hatching to the draw mode of a filled polygon, unless it is
+    /// already hatched.
+    pub(crate) fn shade(
+        &self,
+        light: &Light,
+        poly: &Polygon3,
+        eye: &Eye,
+        draw_mode: &DrawMode,
+    ) -> DrawMode {
+        let mut draw_mode = draw_mode.clone();
+        if draw_mode.fill && draw_mode.hatch.is_none() {
+            draw_mode.hatch = self.hatch(light.brightness(poly, eye));
+        }
+        draw_mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projection::types3::{pt3, vec3};
+
+    #[test]
+    fn test_brightness() {
+        let eye = Eye::Direction(Vector3::z());
+        let square = |points: Vec<Point3>| Polygon3::new(points, pt3(0., 0., 0.));
+        let facing = square(vec![
+            pt3(-1., -1., 0.),
+            pt3(1., -1., 0.),
+            pt3(1., 1., 0.),
+            pt3(-1., 1., 0.),
+        ]);
+        let reversed = square(facing.points.iter().rev().cloned().collect());
+
+        let overhead = Light::Directional(vec3(0., 0., 2.));
+        assert_eq!(1., overhead.brightness(&facing, &eye));
+        assert_eq!(1., overhead.brightness(&reversed, &eye));
+
+        let behind = Light::Directional(vec3(0., 0., -1.));
+        assert_eq!(0., behind.brightness(&facing, &eye));
+
+        let angled = Light::Point(pt3(0., 1., 1.));
+        let expected = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((angled.brightness(&facing, &eye) - expected).abs() < 1e-12);
+    }
+}
@@ -1,8 +1,13 @@
 //! Tools for 3D scene construction.
 
 pub mod apply;
+pub(crate) mod bsp;
+pub mod camera;
 pub mod form;
 pub mod isometric;
+pub mod light;
+pub mod parallel;
+pub mod parametric;
 pub mod platonic;
 pub mod polygon3;
 pub mod scene3;
@@ -0,0 +1,129 @@
+//! Parallel projections, used to view a `Scene3` without a camera.
+//!
+//! The z axis is up. Each projection maps the scene into view space, where
+//! x is to the right, y is up and z points towards the viewer; dropping z
+//! then gives the drawing.
+
+use crate::projection::isometric::isometric_projection;
+use nalgebra::{Matrix3, Rotation3, UnitQuaternion, Vector3};
+use std::f64::consts::PI;
+
+/// A named parallel projection.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Projection {
+    /// All three axes are equally foreshortened.
+    #[default]
+    Isometric,
+    /// The two horizontal axes are equally foreshortened, viewed from
+    /// `elevation` radians above the horizontal.
+    Dimetric { elevation: f64 },
+    /// Each axis is foreshortened differently, viewed from `azimuth` radians
+    /// around the vertical axis and `elevation` radians above the horizontal.
+    Trimetric { azimuth: f64, elevation: f64 },
+    /// An oblique view of the front (the x-z plane), with depth (the y axis)
+    /// drawn at full length at `angle` radians from the horizontal.
+    Cavalier { angle: f64 },
+    /// An oblique view of the front, with depth drawn at half length at
+    /// `angle` radians from the horizontal.
+    Cabinet { angle: f64 },
+    /// Looking down from above, with the y axis up the page.
+    Top,
+    /// Looking from the front, along the y axis.
+    Front,
+    /// Looking from the right side, along the x axis.
+    Side,
+    /// Any other linear map into view space.
+    Custom(Matrix3<f64>),
+}
+
+impl Projection {
+    /// The linear map from the scene into view space.
+    pub fn matrix(&self) -> Matrix3<f64> {
+        match *self {
+            Projection::Isometric => isometric_projection().into_inner(),
+            Projection::Dimetric { elevation } => axonometric(PI / 4., elevation),
+            Projection::Trimetric { azimuth, elevation } => axonometric(azimuth, elevation),
+            Projection::Cavalier { angle } => oblique(angle, 1.),
+            Projection::Cabinet { angle } => oblique(angle, 0.5),
+            Projection::Top => Matrix3::identity(),
+            Projection::Front => axonometric(0., 0.),
+            Projection::Side => axonometric(-PI / 2., 0.),
+            Projection::Custom(matrix) => matrix,
+        }
+    }
+}
+
+/// Use a rotation into view space as a custom projection.
+impl From<Rotation3<f64>> for Projection {
+    fn from(rotation: Rotation3<f64>) -> Self {
+        Projection::Custom(rotation.into_inner())
+    }
+}
+
+/// Use a rotation into view space as a custom projection.
+impl From<UnitQuaternion<f64>> for Projection {
+    fn from(rotation: UnitQuaternion<f64>) -> Self {
+        Projection::Custom(rotation.to_rotation_matrix().into_inner())
+    }
+}
+
+/// Rotate the scene by `azimuth` around the vertical axis, then tilt it so
+/// that it is seen from `elevation` above the horizontal.
+fn axonometric(azimuth: f64, elevation: f64) -> Matrix3<f64> {
+    let turn = Rotation3::from_scaled_axis(Vector3::z() * azimuth);
+    let tilt = Rotation3::from_scaled_axis(Vector3::x() * (elevation - PI / 2.));
+    (tilt * turn).into_inner()
+}
+
+/// Draw the front of the scene as it is, and its depth at `angle` to the
+/// horizontal, scaled by `depth`.
+fn oblique(angle: f64, depth: f64) -> Matrix3<f64> {
+    // The columns are the images of the x, y and z axes.
+    Matrix3::from_columns(&[
+        Vector3::x(),
+        Vector3::new(depth * angle.cos(), depth * angle.sin(), -1.),
+        Vector3::y(),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_views() {
+        let close = |a: Matrix3<f64>, b: Matrix3<f64>| (a - b).norm() < 1e-12;
+        assert!(close(
+            isometric_projection().into_inner(),
+            axonometric(PI / 4., (1. / 3_f64.sqrt()).asin())
+        ));
+
+        // Up is up in the front and side views, and depth is into the page.
+        for view in &[Projection::Front, Projection::Side] {
+            let m = view.matrix();
+            assert!((m * Vector3::z() - Vector3::y()).norm() < 1e-12);
+        }
+        let front = Projection::Front.matrix();
+        assert!((front * Vector3::y() + Vector3::z()).norm() < 1e-12);
+        let side = Projection::Side.matrix();
+        assert!((side * Vector3::x() - Vector3::z()).norm() < 1e-12);
+
+        // Cabinet draws a unit of depth as half a unit at the given angle.
+        let cabinet = Projection::Cabinet { angle: PI / 4. }.matrix();
+        let receding = cabinet * Vector3::y();
+        assert!((receding.xy().norm() - 0.5).abs() < 1e-12);
+        assert!((receding.y - receding.x).abs() < 1e-12);
+
+        // Rotations convert to custom projections.
+        let rotation = isometric_projection();
+        assert!(close(
+            Projection::from(rotation).matrix(),
+            Projection::Isometric.matrix()
+        ));
+        let quaternion = UnitQuaternion::from_rotation_matrix(&rotation);
+        assert!(close(
+            Projection::from(quaternion).matrix(),
+            Projection::Isometric.matrix()
+        ));
+    }
+}
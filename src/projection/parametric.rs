@@ -0,0 +1,203 @@
+//! Parametric 3D shapes, built from flat polygons. Each is centered on the
+//! origin with its axis along z, and its faces are wound counter-clockwise
+//! as seen from outside.
+
+use crate::projection::form::Form;
+use crate::projection::platonic::icosahedron_faces;
+use crate::projection::polygon3::Polygon3;
+use crate::projection::types3::Point3;
+use std::f64::consts::PI;
+
+/// Profile points closer than this to the axis are treated as lying on it.
+const AXIS_TOLERANCE: f64 = 1e-9;
+/// The fewest slices around an axis, or faces around a tube, that enclose
+/// a volume.
+const MIN_SEGMENTS: usize = 3;
+
+/// Construct a sphere of radius 1 from `segments` slices around the z axis
+/// and `rings` bands from pole to pole. At least 3 segments and 2 rings are
+/// used.
+pub fn sphere(segments: usize, rings: usize) -> Form {
+    let rings = rings.max(2);
+    let profile = (0..=rings)
+        .map(|i| {
+            let angle = PI * i as f64 / rings as f64 - PI / 2.;
+            (angle.cos(), angle.sin())
+        })
+        .collect();
+    Form::new(revolve(profile, segments, false))
+}
+
+/// Construct a sphere of radius 1 by dividing each face of an icosahedron
+/// into four triangles `subdivisions` times. Unlike `sphere`, the faces
+/// are all of similar size.
+pub fn icosphere(subdivisions: usize) -> Form {
+    let mut triangles: Vec<[Point3; 3]> = icosahedron_faces()
+        .into_iter()
+        .map(|f| [f.points[0], f.points[1], f.points[2]])
+        .collect();
+    let midpoint = |a: &Point3, b: &Point3| Point3::from((a.coords + b.coords).normalize());
+
+    for _ in 0..subdivisions {
+        triangles = triangles
+            .iter()
+            .flat_map(|[a, b, c]| {
+                let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                vec![[*a, ab, ca], [ab, *b, bc], [ca, bc, *c], [ab, bc, ca]]
+            })
+            .collect();
+    }
+
+    Form::new(
+        triangles
+            .iter()
+            .map(|t| Polygon3::from_points(t.to_vec()))
+            .collect(),
+    )
+}
+
+/// Construct a cylinder of radius 1 from z = -1 to z = 1, with `segments`
+/// faces around its side. At least 3 segments are used.
+pub fn cylinder(segments: usize) -> Form {
+    Form::new(revolve(vec![(1., -1.), (1., 1.)], segments, false))
+}
+
+/// Construct a cone with a base of radius 1 at z = -1 and its tip at z = 1,
+/// with `segments` faces around its side. At least 3 segments are used.
+pub fn cone(segments: usize) -> Form {
+    Form::new(revolve(vec![(1., -1.), (0., 1.)], segments, false))
+}
+
+/// Construct a torus around the z axis, with a tube of radius
+/// `minor_radius` whose center is at distance 1 from the axis. The torus has
+/// `segments` slices around the axis and `sides` faces around the tube. At
+/// least 3 of each are used.
+pub fn torus(minor_radius: f64, segments: usize, sides: usize) -> Form {
+    let sides = sides.max(MIN_SEGMENTS);
+    let profile = (0..sides)
+        .map(|i| {
+            let angle = 2. * PI * i as f64 / sides as f64;
+            (1. + minor_radius * angle.cos(), minor_radius * angle.sin())
+        })
+        .collect();
+    Form::new(revolve(profile, segments, true))
+}
+
+/// Construct a capsule: a cylinder of radius 1 from z = -`half_length` to
+/// z = `half_length`, capped by hemispheres. The capsule has `segments`
+/// slices around the z axis and `rings` bands in each hemisphere. At least 3
+/// segments and 1 ring are used.
+pub fn capsule(half_length: f64, segments: usize, rings: usize) -> Form {
+    let rings = rings.max(1);
+    let arc = |from: usize, to: usize, z: f64| {
+        (from..=to).map(move |i| {
+            let angle = PI / 2. * i as f64 / rings as f64 - PI / 2.;
+            (angle.cos(), angle.sin() + z)
+        })
+    };
+    let profile = arc(0, rings, -half_length)
+        .chain(arc(rings, 2 * rings, half_length))
+        .collect();
+    Form::new(revolve(profile, segments, false))
+}
+
+/// Revolve a profile of `(radius, z)` points around the z axis in
+/// `segments` steps. The profile runs from bottom to top on the outside of
+/// the shape. An open profile is closed by flat caps at any end that is off
+/// the axis.
+fn revolve(profile: Vec<(f64, f64)>, segments: usize, closed: bool) -> Vec<Polygon3> {
+    let segments = segments.max(MIN_SEGMENTS);
+    let on_axis = |(r, _): (f64, f64)| r.abs() < AXIS_TOLERANCE;
+    let point = |(r, z): (f64, f64), j: usize| {
+        let angle = 2. * PI * (j % segments) as f64 / segments as f64;
+        Point3::new(r * angle.cos(), r * angle.sin(), z)
+    };
+
+    let mut polys = Vec::new();
+    let bands = if closed {
+        profile.len()
+    } else {
+        profile.len() - 1
+    };
+    for i in 0..bands {
+        let (low, high) = (profile[i], profile[(i + 1) % profile.len()]);
+        for j in 0..segments {
+            // A band that meets the axis is made of triangles.
+            let mut points = vec![point(low, j)];
+            if !on_axis(low) {
+                points.push(point(low, j + 1));
+            }
+            if !on_axis(high) {
+                points.push(point(high, j + 1));
+            }
+            points.push(point(high, j));
+            if points.len() > 2 {
+                polys.push(Polygon3::from_points(points));
+            }
+        }
+    }
+
+    if !closed {
+        let (bottom, top) = (profile[0], profile[profile.len() - 1]);
+        if !on_axis(bottom) {
+            polys.push(Polygon3::from_points(
+                (0..segments).rev().map(|j| point(bottom, j)).collect(),
+            ));
+        }
+        if !on_axis(top) {
+            polys.push(Polygon3::from_points(
+                (0..segments).map(|j| point(top, j)).collect(),
+            ));
+        }
+    }
+
+    polys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projection::types3::{pt3, Vector3};
+
+    /// Check that every face is flat and faces away from the given point on
+    /// the inside of the shape.
+    fn check(form: &Form, faces: usize, inside: impl Fn(&Point3) -> Point3) {
+        assert_eq!(faces, form.polys.len());
+        for poly in &form.polys {
+            let normal = poly.normal().normalize();
+            assert!(normal.dot(&(poly.center - inside(&poly.center))) > 0.);
+            for p in &poly.points {
+                assert!(normal.dot(&(p - poly.center)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shapes() {
+        let origin = |_: &Point3| pt3(0., 0., 0.);
+        let axis = |p: &Point3| pt3(0., 0., p.z.clamp(-1., 1.));
+        check(&sphere(12, 6), 72, origin);
+        check(&icosphere(2), 320, origin);
+        check(&cylinder(10), 12, origin);
+        check(&cone(10), 11, origin);
+        check(&capsule(1., 10, 4), 90, axis);
+        check(&torus(0.25, 12, 8), 96, |p: &Point3| {
+            Point3::from(Vector3::new(p.x, p.y, 0.).normalize())
+        });
+
+        // Too few segments, rings or sides are raised to the minimum.
+        check(&sphere(0, 0), 6, origin);
+        check(&cylinder(1), 5, origin);
+        check(&capsule(1., 0, 0), 9, axis);
+        check(&torus(0.25, 12, 0), 36, |p: &Point3| {
+            Point3::from(Vector3::new(p.x, p.y, 0.).normalize())
+        });
+
+        // Icosphere vertices lie on the unit sphere.
+        for poly in &icosphere(1).polys {
+            for p in &poly.points {
+                assert!((p.coords.norm() - 1.).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -1,27 +1,30 @@
-//! Implements some basic Platonic solids.
-//! Currently only two are implemented (PRs accepted for the others).
+//! Implements the five Platonic solids. Each is centered on the origin, and
+//! its faces are wound counter-clockwise as seen from outside.
 
 use crate::projection::form::Form;
 use crate::projection::polygon3::Polygon3;
-use crate::projection::types3::Point3;
+use crate::projection::types3::{Point3, Vector3};
 
-/// Construct a tetrahedron `Form`.
+/// The golden ratio.
+const PHI: f64 = 1.618_033_988_749_895;
+
+/// Construct a tetrahedron `Form`, with its vertices at distance 1 from
+/// the origin.
 pub fn tetrahedron() -> Form {
-    let p1 = Point3::new((8. / 9. as f64).sqrt(), 0., -1. / 3.);
-    let p2 = Point3::new(-(2. / 9. as f64).sqrt(), (2. / 3. as f64).sqrt(), -1. / 3.);
-    let p3 = Point3::new(-(2. / 9. as f64).sqrt(), -(2. / 3. as f64).sqrt(), -1. / 3.);
+    let p1 = Point3::new((8. / 9_f64).sqrt(), 0., -1. / 3.);
+    let p2 = Point3::new(-(2. / 9_f64).sqrt(), (2. / 3_f64).sqrt(), -1. / 3.);
+    let p3 = Point3::new(-(2. / 9_f64).sqrt(), -(2. / 3_f64).sqrt(), -1. / 3.);
     let p4 = Point3::new(0., 0., 1.);
-    let origin = Point3::new(0., 0., 0.);
 
     Form::new(vec![
-        Polygon3::new(vec![p1, p2, p4], (p1+(p2-origin)+(p4-origin))/3.),
-        Polygon3::new(vec![p2, p3, p4], (p2+(p3-origin)+(p4-origin))/3.),
-        Polygon3::new(vec![p3, p1, p4], (p3+(p1-origin)+(p4-origin))/3.),
-        Polygon3::new(vec![p1, p2, p3], (p1+(p2-origin)+(p3-origin))/3.),
+        outward(vec![p1, p2, p4]),
+        outward(vec![p2, p3, p4]),
+        outward(vec![p3, p1, p4]),
+        outward(vec![p1, p2, p3]),
     ])
 }
 
-/// Construct a cube `Form`.
+/// Construct a cube `Form`, with sides of length 1.
 pub fn cube() -> Form {
     let p000 = Point3::new(-0.5, -0.5, -0.5);
     let p001 = Point3::new(-0.5, -0.5, 0.5);
@@ -33,11 +36,141 @@ pub fn cube() -> Form {
     let p111 = Point3::new(0.5, 0.5, 0.5);
 
     Form::new(vec![
-        Polygon3::new(vec![p000, p001, p011, p010], Point3::new(-0.5, 0., 0.)),
-        Polygon3::new(vec![p100, p101, p111, p110], Point3::new(0.5, 0., 0.)),
-        Polygon3::new(vec![p000, p001, p101, p100], Point3::new(0., -0.5, 0.)),
-        Polygon3::new(vec![p010, p011, p111, p110], Point3::new(0., 0.5, 0.)),
-        Polygon3::new(vec![p000, p010, p110, p100], Point3::new(0., 0., -0.5)),
-        Polygon3::new(vec![p001, p011, p111, p101], Point3::new(0., 0., 0.5)),
+        outward(vec![p000, p001, p011, p010]),
+        outward(vec![p100, p101, p111, p110]),
+        outward(vec![p000, p001, p101, p100]),
+        outward(vec![p010, p011, p111, p110]),
+        outward(vec![p000, p010, p110, p100]),
+        outward(vec![p001, p011, p111, p101]),
     ])
 }
+
+/// Construct an octahedron `Form`, with its vertices at distance 1 from
+/// the origin.
+pub fn octahedron() -> Form {
+    let mut polys = Vec::new();
+    for &x in &[-1., 1.] {
+        for &y in &[-1., 1.] {
+            for &z in &[-1., 1.] {
+                polys.push(outward(vec![
+                    Point3::new(x, 0., 0.),
+                    Point3::new(0., y, 0.),
+                    Point3::new(0., 0., z),
+                ]));
+            }
+        }
+    }
+    Form::new(polys)
+}
+
+/// Construct an icosahedron `Form`, with its vertices at distance 1 from
+/// the origin.
+pub fn icosahedron() -> Form {
+    Form::new(icosahedron_faces())
+}
+
+/// Construct a dodecahedron `Form`, with its vertices at distance 1 from
+/// the origin.
+pub fn dodecahedron() -> Form {
+    // The dodecahedron is the dual of the icosahedron: each of its faces
+    // joins the centers of the icosahedron faces around one vertex.
+    let faces = icosahedron_faces();
+    let polys = icosahedron_vertices()
+        .iter()
+        .map(|vertex| {
+            let corners: Vec<Point3> = faces
+                .iter()
+                .filter(|f| f.points.contains(vertex))
+                .map(|f| Point3::from(f.center.coords.normalize()))
+                .collect();
+            outward(around(vertex.coords, corners))
+        })
+        .collect();
+    Form::new(polys)
+}
+
+/// The vertices of an icosahedron, at distance 1 from the origin.
+fn icosahedron_vertices() -> Vec<Point3> {
+    let mut vertices = Vec::new();
+    for &a in &[-1., 1.] {
+        for &b in &[-PHI, PHI] {
+            vertices.push(Point3::new(0., a, b));
+            vertices.push(Point3::new(a, b, 0.));
+            vertices.push(Point3::new(b, 0., a));
+        }
+    }
+    vertices
+        .into_iter()
+        .map(|v| Point3::from(v.coords.normalize()))
+        .collect()
+}
+
+/// The faces of an icosahedron: every triangle of vertices that are
+/// adjacent to each other.
+pub(crate) fn icosahedron_faces() -> Vec<Polygon3> {
+    let vertices = icosahedron_vertices();
+    let edge = (vertices[0] - vertices[1])
+        .norm()
+        .min((vertices[0] - vertices[2]).norm());
+    let adjacent = |a: &Point3, b: &Point3| ((a - b).norm() - edge).abs() < 1e-9;
+
+    let mut faces = Vec::new();
+    for (i, a) in vertices.iter().enumerate() {
+        for (j, b) in vertices.iter().enumerate().skip(i + 1) {
+            for c in vertices.iter().skip(j + 1) {
+                if adjacent(a, b) && adjacent(b, c) && adjacent(a, c) {
+                    faces.push(outward(vec![*a, *b, *c]));
+                }
+            }
+        }
+    }
+    faces
+}
+
+/// Order points by their angle around an axis.
+fn around(axis: Vector3, mut points: Vec<Point3>) -> Vec<Point3> {
+    let u = axis.cross(&Vector3::x()).normalize();
+    let v = axis.cross(&u).normalize();
+    let angle = |p: &Point3| p.coords.dot(&v).atan2(p.coords.dot(&u));
+    points.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap());
+    points
+}
+
+/// Construct a face of a solid centered on the origin, wound so that it is
+/// counter-clockwise as seen from outside.
+fn outward(mut points: Vec<Point3>) -> Polygon3 {
+    let poly = Polygon3::from_points(points.clone());
+    if poly.normal().dot(&poly.center.coords) < 0. {
+        points.reverse();
+        return Polygon3::from_points(points);
+    }
+    poly
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solids() {
+        let solids = vec![
+            (tetrahedron(), 4, 3),
+            (cube(), 6, 4),
+            (octahedron(), 8, 3),
+            (dodecahedron(), 12, 5),
+            (icosahedron(), 20, 3),
+        ];
+        for (form, faces, sides) in solids {
+            assert_eq!(faces, form.polys.len());
+            for poly in &form.polys {
+                assert_eq!(sides, poly.points.len());
+                // Faces are flat, and face away from the center.
+                let normal = poly.normal().normalize();
+                assert!(normal.dot(&poly.center.coords) > 0.);
+                for p in &poly.points {
+                    assert!(normal.dot(&(p - poly.center)).abs() < 1e-9);
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,10 @@
 //! Three dimensional polygons.
 
+use crate::draw_mode::DrawMode;
 use crate::geom::polygon::Polygon;
 use crate::projection::apply::Apply;
 use crate::projection::transform::Transform;
-use crate::projection::types3::Point3;
+use crate::projection::types3::{Point3, Vector3};
 use crate::types::Point;
 
 /// Represents a polygon in terms of 3D points.
@@ -35,11 +36,35 @@ impl Polygon3 {
 }
 
 impl Polygon3 {
+    /// Compute the (unnormalized) normal of this polygon using Newell's method,
+    /// which is robust to concave and slightly non-planar polygons. The normal
+    /// points towards the side from which the points appear counter-clockwise.
+    pub fn normal(&self) -> Vector3 {
+        let mut normal = Vector3::zeros();
+        for (i, p) in self.points.iter().enumerate() {
+            let q = self.points[(i + 1) % self.points.len()];
+            normal.x += (p.y - q.y) * (p.z + q.z);
+            normal.y += (p.z - q.z) * (p.x + q.x);
+            normal.z += (p.x - q.x) * (p.y + q.y);
+        }
+        normal
+    }
+
     /// Construct a new polygon from a list of 3D points.
     pub fn new(points: Vec<Point3>, center: Point3) -> Polygon3 {
         Polygon3 { points, center }
     }
 
+    /// Construct a new polygon from a list of 3D points, with its center at
+    /// the average of the points.
+    pub fn from_points(points: Vec<Point3>) -> Polygon3 {
+        let sum = points
+            .iter()
+            .fold(Vector3::zeros(), |acc, p| acc + p.coords);
+        let center = Point3::from(sum / points.len() as f64);
+        Polygon3 { points, center }
+    }
+
     /// Construct a new 3D polygon from a 2D polygon.
     pub fn from_poly(poly: &Polygon) -> Polygon3 {
         let points: Vec<Point3> = poly
@@ -48,7 +73,10 @@ impl Polygon3 {
             .iter()
             .map(|p: &Point| Point3::new(p.x, p.y, 0.0))
             .collect();
-        Polygon3 { points, center: Point3::new(0., 0., 0.) }
+        Polygon3 {
+            points,
+            center: Point3::new(0., 0., 0.),
+        }
     }
 }
 
@@ -61,3 +89,24 @@ impl Apply for Polygon3 {
         self
     }
 }
+
+/// A polygon queued for drawing, with a flag for each edge (from point `i`
+/// to point `i + 1`) that indicates whether the edge should be stroked.
+#[derive(Clone)]
+pub(crate) struct Face {
+    pub poly: Polygon3,
+    pub draw_mode: DrawMode,
+    pub edges: Vec<bool>,
+}
+
+impl Face {
+    /// Construct a face with all of its edges stroked.
+    pub fn new(poly: Polygon3, draw_mode: DrawMode) -> Face {
+        let edges = vec![true; poly.points.len()];
+        Face {
+            poly,
+            draw_mode,
+            edges,
+        }
+    }
+}
@@ -3,23 +3,72 @@
 use crate::draw_mode::DrawMode;
 use crate::geom::polygon::Polygon;
 use crate::projection::apply::Apply;
+use crate::projection::bsp::{bsp_order, Eye};
+use crate::projection::camera::Camera;
 use crate::projection::form::Form;
-use crate::projection::isometric::isometric_projection;
-use crate::projection::polygon3::Polygon3;
+use crate::projection::light::{Light, Shading};
+use crate::projection::parallel::Projection;
+use crate::projection::polygon3::{Face, Polygon3};
 use crate::projection::transform::Transform;
+use crate::projection::types3::{Point3, Vector3};
 use crate::scene::Scene;
-use na::Rotation3;
+use nalgebra::Matrix3;
+use std::collections::HashMap;
+
+/// Points closer than this in each coordinate are treated as the same when
+/// matching up the edges of neighboring polygons.
+const EDGE_TOLERANCE: f64 = 1e-9;
+
+/// Strategy for ordering polygons back-to-front when converting to 2D.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DepthSort {
+    /// Sort polygons by the depth of their `center`. This is fast, but
+    /// gives the wrong order for polygons that intersect or interleave.
+    Center,
+    /// Order polygons with a binary space partitioning tree, splitting them
+    /// where necessary. This gives the correct order for arbitrary meshes.
+    Bsp,
+}
+
+/// Selects which edges of each polygon are stroked when converting to 2D.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Edges {
+    /// Stroke every edge of every polygon.
+    #[default]
+    All,
+    /// Stroke only the edges on the outline of each mesh as seen by the
+    /// viewer (between a polygon facing the viewer and one facing away, or
+    /// with no neighbor), and creases where the normals of two neighboring
+    /// polygons are more than `crease_angle` radians apart. Each edge is
+    /// stroked once. Polygons must be wound consistently, as with the
+    /// built-in solids.
+    Silhouette { crease_angle: f64 },
+}
 
 /// Represents a 3D scene as a set of 3D polygons (with associated
 /// draw modes). Also acts as a builder for a 2D scene by storing
-/// a rotation and perspective.
+/// a projection and perspective, or a camera.
 pub struct Scene3 {
     /// Polygons and drawing instructions.
     pub polys: Vec<(Polygon3, DrawMode)>,
     /// Perspective to apply when converting to 2D.
     pub perspective: f64,
-    /// Rotation to apply when converting to 2D.
-    pub projection: Rotation3<f64>,
+    /// Projection to apply when converting to 2D.
+    pub projection: Projection,
+    /// A camera to view the scene with when converting to 2D. If given, this
+    /// is used instead of `perspective` and `projection`.
+    pub camera: Option<Camera>,
+    /// How polygons are ordered when converting to 2D.
+    pub depth_sort: DepthSort,
+    /// A light to shade filled polygons with. If not given, polygons are
+    /// not shaded.
+    pub light: Option<Light>,
+    /// How shading is turned into hatching.
+    pub shading: Shading,
+    /// Whether to skip polygons that face away from the viewer.
+    pub back_face_culling: bool,
+    /// Which edges of each polygon are stroked.
+    pub edges: Edges,
 }
 
 fn dangerous_compare(x: &f64, y: &f64) -> std::cmp::Ordering {
@@ -32,7 +81,13 @@ impl Scene3 {
         Scene3 {
             polys: Vec::new(),
             perspective: 1.0,
-            projection: isometric_projection(),
+            projection: Projection::Isometric,
+            camera: None,
+            depth_sort: DepthSort::Center,
+            light: None,
+            shading: Default::default(),
+            back_face_culling: false,
+            edges: Edges::All,
         }
     }
 
@@ -42,6 +97,53 @@ impl Scene3 {
         self
     }
 
+    /// Set the projection used when converting to 2D. A `Rotation3` or
+    /// `UnitQuaternion` is used as a custom projection.
+    pub fn projection(mut self, projection: impl Into<Projection>) -> Scene3 {
+        self.projection = projection.into();
+        self
+    }
+
+    /// View the scene with the given camera.
+    pub fn camera(mut self, camera: Camera) -> Scene3 {
+        self.camera = Some(camera);
+        self
+    }
+
+    /// Set the strategy used to order polygons back-to-front.
+    pub fn depth_sort(mut self, depth_sort: DepthSort) -> Scene3 {
+        self.depth_sort = depth_sort;
+        self
+    }
+
+    /// Shade filled polygons that do not already have a hatch pattern, by
+    /// hatching them according to how brightly they are lit.
+    pub fn light(mut self, light: Light) -> Scene3 {
+        self.light = Some(light);
+        self
+    }
+
+    /// Set how shading is turned into hatching.
+    pub fn shading(mut self, shading: Shading) -> Scene3 {
+        self.shading = shading;
+        self
+    }
+
+    /// Skip polygons that face away from the viewer, whose points appear
+    /// clockwise once projected. This hides the back of closed solids whose
+    /// polygons are wound counter-clockwise as seen from outside, as the
+    /// built-in solids are, without relying on fill.
+    pub fn back_face_culling(mut self, back_face_culling: bool) -> Scene3 {
+        self.back_face_culling = back_face_culling;
+        self
+    }
+
+    /// Select which edges of each polygon are stroked.
+    pub fn edges(mut self, edges: Edges) -> Scene3 {
+        self.edges = edges;
+        self
+    }
+
     /// Add a 3D polygon to the scene with the default draw mode.
     pub fn add_poly(&mut self, poly: Polygon3) {
         self.polys.push((poly, Default::default()));
@@ -64,30 +166,237 @@ impl Scene3 {
         )
     }
 
-    fn project(&self) -> Vec<(Polygon, DrawMode)> {
-        let mut v: Vec<(f64, Polygon, DrawMode)> = self
+    /// The position of the viewer implied by the camera or perspective.
+    fn eye(&self) -> Eye {
+        if self.camera.is_some() {
+            Eye::Point(Point3::origin())
+        } else if self.perspective < 1. {
+            Eye::Point(Point3::new(0., 0., 1. / (1. - self.perspective)))
+        } else {
+            Eye::Direction(Vector3::z())
+        }
+    }
+
+    /// The position of the viewer in the coordinates of the scene, before
+    /// it is viewed with the camera or projection.
+    fn world_eye(&self) -> Eye {
+        if let Some(camera) = &self.camera {
+            return Eye::Point(camera.eye);
+        }
+        let inverse = self
+            .projection
+            .matrix()
+            .try_inverse()
+            .unwrap_or_else(Matrix3::identity);
+        match self.eye() {
+            Eye::Point(p) => Eye::Point(Point3::from(inverse * p.coords)),
+            Eye::Direction(v) => Eye::Direction(inverse * v),
+        }
+    }
+
+    /// Hatch filled polygons according to the light. This is done before
+    /// projecting, since oblique and custom projections do not preserve
+    /// angles and would distort the light and the normals.
+    fn shade(&mut self) {
+        if let Some(light) = self.light.take() {
+            let eye = self.world_eye();
+            let shading = self.shading;
+            for (poly, draw_mode) in &mut self.polys {
+                *draw_mode = shading.shade(&light, poly, &eye, draw_mode);
+            }
+        }
+    }
+
+    fn project(&self) -> Vec<Face> {
+        let eye = self.eye();
+        let mut faces: Vec<Face> = self
             .polys
             .iter()
-            .map(|d| (d.0.center.z, d.0.to_2d(self.perspective), d.1.clone()))
+            .map(|(poly, draw_mode)| Face::new(poly.clone(), draw_mode.clone()))
             .collect();
 
-        v.sort_by(|x, y| dangerous_compare(&x.0, &y.0));
+        if let Edges::Silhouette { crease_angle } = self.edges {
+            silhouette_edges(&mut faces, &eye, crease_angle);
+        }
 
-        v.into_iter().map(|d| (d.1, d.2)).collect()
+        let faces: Vec<Face> = faces
+            .into_iter()
+            .filter_map(|face| match &self.camera {
+                Some(camera) => camera.clip(&face),
+                None => Some(face),
+            })
+            .collect();
+
+        match self.depth_sort {
+            DepthSort::Center => {
+                let mut faces = faces;
+                faces.sort_by(|x, y| dangerous_compare(&x.poly.center.z, &y.poly.center.z));
+                faces
+            }
+            DepthSort::Bsp => bsp_order(faces, eye),
+        }
     }
 
-    /// Project the scene into 2D.
-    pub fn to_2d(self) -> Scene {
+    /// Project the scene into 2D. With a camera, the result is cropped to
+    /// the camera's field of view.
+    pub fn to_2d(mut self) -> Scene {
+        self.shade();
+        let edges = self.edges;
+        let back_face_culling = self.back_face_culling;
+        let (mut faces, window): (Vec<(Polygon, Face)>, _) = match self.camera {
+            Some(camera) => {
+                let viewed = self.apply(&camera.view());
+                let faces = viewed
+                    .project()
+                    .into_iter()
+                    .map(|face| (camera.project_view_poly(&face.poly), face))
+                    .collect();
+                (faces, Some(camera.window()))
+            }
+            None => {
+                // TODO: this is hacky
+                let proj = self.projection.matrix();
+                let projected = self.apply(&proj);
+                let faces = projected
+                    .project()
+                    .into_iter()
+                    .map(|face| (face.poly.to_2d(projected.perspective), face))
+                    .collect();
+                (faces, None)
+            }
+        };
+
+        if back_face_culling {
+            // The drawing's y axis points down, so faces wound
+            // counter-clockwise as seen by the viewer appear clockwise.
+            faces.retain(|(poly, _)| poly.points.signed_area() < 0.);
+        }
+        if let Edges::Silhouette { .. } = edges {
+            stroke_once(&mut faces);
+        }
+
         let mut s = Scene::new();
+        for (poly, face) in faces {
+            s.add_poly_with_edges(&poly, face.draw_mode, &face.edges);
+        }
+        if let Some((corner1, corner2)) = window {
+            s.crop(corner1, corner2);
+        }
+        s
+    }
+}
+
+/// Whether a polygon faces the viewer, with its points counter-clockwise as
+/// seen from the eye.
+fn faces_eye(poly: &Polygon3, eye: &Eye) -> bool {
+    poly.normal().dot(&eye.view(&poly.center)) > 0.
+}
+
+/// Identifies an edge by its end points, in either direction.
+type EdgeKey = ([i64; 3], [i64; 3]);
+
+fn edge_key(a: Point3, b: Point3) -> EdgeKey {
+    let key = |p: Point3| {
+        [
+            (p.x / EDGE_TOLERANCE).round() as i64,
+            (p.y / EDGE_TOLERANCE).round() as i64,
+            (p.z / EDGE_TOLERANCE).round() as i64,
+        ]
+    };
+    let (a, b) = (key(a), key(b));
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// The key of each edge of a polygon, from point `i` to point `i + 1`.
+fn edge_keys(points: &[Point3]) -> Vec<EdgeKey> {
+    (0..points.len())
+        .map(|i| edge_key(points[i], points[(i + 1) % points.len()]))
+        .collect()
+}
 
-        // TODO: this is hacky
-        let proj = self.projection.clone();
+/// Keep only the edges of each face that are on the silhouette as seen from
+/// `eye`, that are creases of more than `crease_angle`, or that are not
+/// shared with another face.
+fn silhouette_edges(faces: &mut [Face], eye: &Eye, crease_angle: f64) {
+    let normals: Vec<Vector3> = faces
+        .iter()
+        .map(|face| {
+            face.poly
+                .normal()
+                .try_normalize(0.)
+                .unwrap_or_else(Vector3::zeros)
+        })
+        .collect();
+    let front: Vec<bool> = faces
+        .iter()
+        .map(|face| faces_eye(&face.poly, eye))
+        .collect();
+    let keys: Vec<Vec<EdgeKey>> = faces
+        .iter()
+        .map(|face| edge_keys(&face.poly.points))
+        .collect();
 
-        for (poly, draw_mode) in self.apply(&proj).project() {
-            s.add_poly_with_draw_mode(&poly, draw_mode)
+    let mut neighbors: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+    for (i, face_keys) in keys.iter().enumerate() {
+        for key in face_keys {
+            neighbors.entry(*key).or_default().push(i);
         }
+    }
 
-        s
+    let min_cos = crease_angle.cos();
+    for (i, face) in faces.iter_mut().enumerate() {
+        face.edges = keys[i]
+            .iter()
+            .map(|key| {
+                let mut others = neighbors[key].iter().filter(|&&j| j != i).peekable();
+                others.peek().is_none()
+                    || others
+                        .any(|&j| front[i] != front[j] || normals[i].dot(&normals[j]) < min_cos)
+            })
+            .collect();
+    }
+}
+
+/// Stroke each edge only on the last face (in drawing order) that strokes
+/// it with the same pen, so that an edge shared by two faces is drawn once,
+/// and is not erased by the fill of the face drawn after it.
+fn stroke_once(faces: &mut [(Polygon, Face)]) {
+    let keys: Vec<Vec<EdgeKey>> = faces
+        .iter()
+        .map(|(poly, _)| {
+            let points: Vec<Point3> = poly
+                .points
+                .0
+                .iter()
+                .map(|p| Point3::new(p.x, p.y, 0.))
+                .collect();
+            edge_keys(&points)
+        })
+        .collect();
+
+    let mut last = HashMap::new();
+    for (k, (_, face)) in faces.iter().enumerate() {
+        for (key, stroked) in keys[k].iter().zip(&face.edges) {
+            if *stroked {
+                last.insert((*key, face.draw_mode.pen), k);
+            }
+        }
+    }
+    for (k, (_, face)) in faces.iter_mut().enumerate() {
+        let pen = face.draw_mode.pen;
+        for (key, stroked) in keys[k].iter().zip(face.edges.iter_mut()) {
+            *stroked = *stroked && last[&(*key, pen)] == k;
+        }
+    }
+}
+
+impl Default for Scene3 {
+    fn default() -> Self {
+        Scene3::new()
     }
 }
 
@@ -98,6 +407,131 @@ impl Apply for Scene3 {
             .into_iter()
             .map(|(poly, draw_mode)| (poly.apply(transform), draw_mode))
             .collect();
+        self.light = self.light.map(|light| light.apply(transform));
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::draw_mode::stroke;
+    use crate::projection::platonic::cube;
+    use std::f64::consts::PI;
+
+    fn line_count(scene3: Scene3, form: Form) -> usize {
+        // A view in which no corner of the cube hides behind another.
+        let mut scene3 = scene3.projection(Projection::Trimetric {
+            azimuth: 0.4,
+            elevation: 0.5,
+        });
+        scene3.add_form(form);
+        let plot = scene3.to_2d().to_plot().unwrap();
+        plot.layers.iter().map(|layer| layer.lines.len()).sum()
+    }
+
+    #[test]
+    fn test_culling_and_silhouettes() {
+        // Without fill, every edge of every face is drawn unless the three
+        // faces facing away are culled.
+        let outlined = || cube().draw_mode(stroke(0));
+        assert_eq!(24, line_count(Scene3::new(), outlined()));
+        let culled = Scene3::new().back_face_culling(true);
+        assert_eq!(12, line_count(culled, outlined()));
+
+        // The visible faces have nine distinct edges, and the outline has six.
+        let creases = Edges::Silhouette {
+            crease_angle: PI / 4.,
+        };
+        assert_eq!(9, line_count(Scene3::new().edges(creases), cube()));
+        let outline = Edges::Silhouette { crease_angle: PI };
+        assert_eq!(6, line_count(Scene3::new().edges(outline), cube()));
+        let culled = Scene3::new().back_face_culling(true).edges(outline);
+        assert_eq!(6, line_count(culled, outlined()));
+    }
+
+    #[test]
+    fn test_culling_keeps_faces_towards_viewer() {
+        // Draw each face of the cube with its own pen, so the surviving
+        // faces can be told apart.
+        let view = Projection::Trimetric {
+            azimuth: 0.4,
+            elevation: 0.5,
+        };
+        let mut scene3 = Scene3::new().projection(view).back_face_culling(true);
+        let faces = cube().polys;
+        for (pen, face) in faces.iter().enumerate() {
+            scene3.add_poly_with_pen(face.clone(), stroke(pen));
+        }
+        let plot = scene3.to_2d().to_plot().unwrap();
+        let drawn: Vec<usize> = plot.layers.iter().map(|layer| layer.pen).collect();
+
+        // The faces whose outward normals point towards the viewer, who
+        // looks along the view space z axis.
+        let towards_viewer = view.matrix().try_inverse().unwrap() * Vector3::z();
+        let visible: Vec<usize> = (0..faces.len())
+            .filter(|i| faces[*i].normal().dot(&towards_viewer) > 0.)
+            .collect();
+        assert_eq!(3, visible.len());
+        assert_eq!(visible, drawn);
+        // The view is from above, so the top is drawn and the bottom is not.
+        let pen_facing = |direction: Vector3| {
+            (0..faces.len())
+                .find(|i| faces[*i].normal().dot(&direction) > 0.99)
+                .unwrap()
+        };
+        assert!(drawn.contains(&pen_facing(Vector3::z())));
+        assert!(!drawn.contains(&pen_facing(-Vector3::z())));
+
+        // Seen from the top, only the upper of two stacked squares is drawn,
+        // at its own size.
+        let square = |z: f64, size: f64| {
+            let corners = [(-1., -1.), (1., -1.), (1., 1.), (-1., 1.)];
+            let points: Vec<Point3> = corners
+                .iter()
+                .map(|(x, y)| Point3::new(x * size, y * size, z))
+                .collect();
+            Polygon3::from_points(points)
+        };
+        let mut scene3 = Scene3::new()
+            .projection(Projection::Top)
+            .back_face_culling(true);
+        let mut bottom = square(-1., 2.);
+        bottom.points.reverse();
+        scene3.add_poly_with_pen(bottom, stroke(1));
+        scene3.add_poly_with_pen(square(1., 1.), stroke(2));
+        let plot = scene3.to_2d().to_plot().unwrap();
+        assert_eq!(1, plot.layers.len());
+        assert_eq!(2, plot.layers[0].pen);
+        assert_eq!(4, plot.layers[0].lines.len());
+        assert!(plot.layers[0]
+            .lines
+            .iter()
+            .all(|l| l.c1.x.abs() == 1. && l.c1.y.abs() == 1.));
+    }
+
+    #[test]
+    fn test_shading_is_independent_of_projection() {
+        // A floor lit from straight above is fully lit in every view, even
+        // though the cavalier projection shears it.
+        let hatch_lines = |projection: Projection| {
+            let mut scene3 = Scene3::new()
+                .projection(projection)
+                .light(Light::Directional(Vector3::z()));
+            scene3.add_poly(Polygon3::from_points(vec![
+                Point3::new(-1., -1., 0.),
+                Point3::new(1., -1., 0.),
+                Point3::new(1., 1., 0.),
+                Point3::new(-1., 1., 0.),
+            ]));
+            let plot = scene3.to_2d().to_plot().unwrap();
+            plot.layers
+                .iter()
+                .map(|layer| layer.lines.len())
+                .sum::<usize>()
+                - 4
+        };
+        assert_eq!(0, hatch_lines(Projection::Isometric));
+        assert_eq!(0, hatch_lines(Projection::Cavalier { angle: PI / 4. }));
+    }
+}
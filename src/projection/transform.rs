@@ -2,7 +2,7 @@
 //! matrices.
 
 use crate::projection::types3::{Point3, Vector3};
-use nalgebra::{Perspective3, Rotation3, Translation3};
+use nalgebra::{Isometry3, Matrix3, Perspective3, Rotation3, Translation3};
 
 /// Indicates that a struct can be used to transform a point.
 pub trait Transform {
@@ -15,6 +15,18 @@ impl Transform for Rotation3<f64> {
     }
 }
 
+impl Transform for Isometry3<f64> {
+    fn transform_point(&self, point: Point3) -> Point3 {
+        self * point
+    }
+}
+
+impl Transform for Matrix3<f64> {
+    fn transform_point(&self, point: Point3) -> Point3 {
+        Point3::from(self * point.coords)
+    }
+}
+
 impl Transform for Translation3<f64> {
     fn transform_point(&self, point: Point3) -> Point3 {
         self * point
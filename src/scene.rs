@@ -1,11 +1,15 @@
 //! Representation and manipulation of 2D scenes.
 
-use crate::draw_mode::DrawMode;
+use crate::draw_mode::{DrawMode, PenMask};
+use crate::error::{Error, Result};
+use crate::geom::clip::{clip_segment, Clip};
+use crate::geom::hatch::Hatch;
 use crate::geom::line_segment::LineSegment;
 use crate::geom::polygon::Polygon;
+use crate::geom::polyline::Polyline;
 use crate::plot::{Layer, Plot};
-use crate::types::Point;
-use rstar::{RTree, RTreeObject, AABB};
+use crate::types::{ObjectId, Point, Vector};
+use rstar::{Envelope, RTree, RTreeObject, AABB};
 use std::collections::BTreeMap;
 
 /// Represents a 2D scene, indexed for efficient addition of
@@ -16,7 +20,45 @@ use std::collections::BTreeMap;
 /// Similar to raster graphics pipelines, polygons are expected
 /// to be added back-to-front.
 pub struct Scene {
-    lines: RTree<LineSegment>,
+    lines: RTree<SceneLine>,
+    /// The number of segments added so far, used to keep them in order.
+    added: usize,
+    /// The number of objects added so far, used to give them IDs.
+    objects: usize,
+    /// Fixed bounds set by `crop`, used instead of the bounds of the lines.
+    frame: Option<AABB<[f64; 2]>>,
+}
+
+/// A line segment in a scene, with the position at which it was added and
+/// the object it came from. Parts of a segment that is split by a fill keep
+/// its position, direction and object.
+#[derive(PartialEq, Clone, Copy)]
+struct SceneLine {
+    line: LineSegment,
+    order: usize,
+    /// The unit direction of the segment as it was added, or zero for an
+    /// empty segment.
+    direction: Vector,
+    object: ObjectId,
+    /// Whether the segment is part of the object's hatching rather than its
+    /// outline.
+    hatch: bool,
+}
+
+impl SceneLine {
+    /// The key that sorts segments in the order they were added, and parts
+    /// of a split segment by how far along it they start.
+    fn sort_key(&self) -> (usize, f64) {
+        (self.order, self.line.c1.coords.dot(&self.direction))
+    }
+}
+
+impl RTreeObject for SceneLine {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.line.envelope()
+    }
 }
 
 impl Scene {
@@ -24,128 +66,171 @@ impl Scene {
     pub fn new() -> Scene {
         Scene {
             lines: RTree::new(),
+            added: 0,
+            objects: 0,
+            frame: None,
         }
     }
 
-    /// Converts a scene to a `Plot` without optimizing it.
-    pub fn to_plot(&self) -> Plot {
-        let bounds = self.lines.root().envelope();
+    /// Converts a scene to a `Plot` without optimizing it. Segments are kept
+    /// in the order they were added, so that polylines stay in order, and
+    /// are labelled with the objects they came from.
+    ///
+    /// Fails with `Error::EmptyScene` if the scene has no lines and has not
+    /// been cropped. A cropped scene with no lines gives a blank plot.
+    pub fn to_plot(&self) -> Result<Plot> {
+        let bounds = self.bounds().ok_or(Error::EmptyScene)?;
         let lower_bound: Point = Point::from(bounds.lower());
         let upper_bound: Point = Point::from(bounds.upper());
 
         let mut layers_map: BTreeMap<usize, Layer> = BTreeMap::new();
 
-        for line in &self.lines {
+        let mut lines: Vec<&SceneLine> = self.lines.iter().collect();
+        lines.sort_by(|a, b| a.sort_key().partial_cmp(&b.sort_key()).unwrap());
+
+        for SceneLine { line, object, .. } in lines {
             let entry = layers_map
                 .entry(line.pen)
                 .or_insert_with(|| Layer::new(line.pen));
-            entry.lines.push(line.clone());
+            entry.lines.push(LineSegment {
+                object: Some(*object),
+                ..*line
+            });
         }
 
-        let layers: Vec<Layer> = layers_map.values().into_iter().map(|x| x.clone()).collect();
+        let layers: Vec<Layer> = layers_map.values().cloned().collect();
 
-        Plot::new(layers, lower_bound, upper_bound)
+        Ok(Plot::new(layers, lower_bound, upper_bound))
     }
 
-    /// Computes the bounds of the scene.
-    pub fn bounds(&self) -> AABB<[f64; 2]> {
-        self.lines.root().envelope()
+    /// Computes the bounds of the scene. If the scene has been cropped, these
+    /// are the bounds of the crop rectangle. Returns `None` for an empty scene
+    /// that has not been cropped.
+    pub fn bounds(&self) -> Option<AABB<[f64; 2]>> {
+        if self.frame.is_some() {
+            return self.frame;
+        }
+        if self.lines.size() == 0 {
+            return None;
+        }
+        Some(self.lines.root().envelope())
     }
 
     /// Adds a single line segment. Does not consider overlaps since lines have an
     /// empty area.
-    pub fn add_segment(&mut self, segment: LineSegment) {
-        self.lines.insert(segment);
+    pub fn add_segment(&mut self, segment: LineSegment) -> ObjectId {
+        let object = self.new_object();
+        self.add_lines(vec![segment], object, false);
+        object
+    }
+
+    /// Adds an open polyline with a given pen. Its segments are kept in order
+    /// when the scene is converted to a `Plot`.
+    pub fn add_polyline(&mut self, polyline: &Polyline, pen: usize) -> ObjectId {
+        let object = self.new_object();
+        self.add_lines(polyline.line_segments_with_pen(pen), object, false);
+        object
+    }
+
+    /// Removes the remaining segments of an object. Lines that it erased when
+    /// it was filled are not restored.
+    pub fn remove(&mut self, id: ObjectId) {
+        self.update(id, |_| None);
+    }
+
+    /// Changes the pen of the outline of an object. Its hatch lines keep
+    /// their pen; use `set_hatch_pen` to change those.
+    pub fn set_pen(&mut self, id: ObjectId, pen: usize) {
+        self.update(id, |entry| {
+            Some(if entry.hatch {
+                entry.line
+            } else {
+                LineSegment { pen, ..entry.line }
+            })
+        });
+    }
+
+    /// Changes the pen of the hatch lines of an object.
+    pub fn set_hatch_pen(&mut self, id: ObjectId, pen: usize) {
+        self.update(id, |entry| {
+            Some(if entry.hatch {
+                LineSegment { pen, ..entry.line }
+            } else {
+                entry.line
+            })
+        });
+    }
+
+    /// Returns the segments of an object that are still in the scene.
+    pub fn segments(&self, id: ObjectId) -> Vec<LineSegment> {
+        let mut lines: Vec<&SceneLine> = self.lines.iter().filter(|l| l.object == id).collect();
+        lines.sort_by(|a, b| a.sort_key().partial_cmp(&b.sort_key()).unwrap());
+        lines.iter().map(|l| l.line).collect()
+    }
+
+    /// Replace or drop each segment of an object.
+    fn update(&mut self, id: ObjectId, f: impl Fn(&SceneLine) -> Option<LineSegment>) {
+        let lines = std::mem::replace(&mut self.lines, RTree::new());
+        let lines: Vec<SceneLine> = lines
+            .iter()
+            .filter_map(|entry| {
+                if entry.object != id {
+                    return Some(*entry);
+                }
+                f(entry).map(|line| SceneLine { line, ..*entry })
+            })
+            .collect();
+        self.lines = RTree::bulk_load(lines);
+    }
+
+    /// Reserve an ID for a new object.
+    fn new_object(&mut self) -> ObjectId {
+        self.objects += 1;
+        ObjectId(self.objects - 1)
+    }
+
+    /// Add segments belonging to an object, as its outline or its hatching.
+    fn add_lines(&mut self, lines: Vec<LineSegment>, object: ObjectId, hatch: bool) {
+        for line in lines {
+            self.lines.insert(SceneLine {
+                line,
+                order: self.added,
+                direction: line
+                    .vector()
+                    .try_normalize(0.)
+                    .unwrap_or_else(Vector::zeros),
+                object,
+                hatch,
+            });
+            self.added += 1;
+        }
     }
 
     /// Simulate "fill" for the given polygon by erasing parts of line segments
     /// which it covers. Does not outline the polygon; this should be done with
     /// `stroke_poly` *after* `fill_poly`.
     pub fn fill_poly(&mut self, poly: &Polygon) {
-        let mut drop_segments: Vec<LineSegment> = Vec::new();
-        let mut new_segments: Vec<LineSegment> = Vec::new();
-
-        for line in self.lines.locate_in_envelope_intersecting(&poly.envelope()) {
-            let mut crossings: Vec<f64> = Vec::new();
-
-            let mut point_loops = vec![&poly.points];
-            point_loops.extend(&poly.holes);
-
-            let mut inter = false;
-            let mut pre = false;
-            let mut post = false;
-
-            for point_loop in point_loops {
-                let point_sides: Vec<(&Point, bool)> = point_loop
-                    .0
-                    .iter()
-                    .map(|p| (p, line.point_side(p)))
-                    .collect();
-
-                let mut last = match point_sides.last() {
-                    Some(p) => p,
-                    None => continue,
-                };
-
-                for point_side in &point_sides {
-                    if point_side.1 != last.1 {
-                        // This line segment crosses.
-                        let f = line
-                            .intersect_lines(&LineSegment::new(*last.0, *point_side.0))
-                            .unwrap();
-
-                        if f < 0. {
-                            pre = true;
-                        } else if f > 1. {
-                            post = true;
-                        } else {
-                            inter = true;
-                        }
-
-                        crossings.push(f);
-                    }
+        self.fill_poly_masked(poly, &PenMask::All)
+    }
 
-                    last = point_side;
-                }
-            }
+    /// Simulate "fill" for the given polygon like `fill_poly`, but only erase
+    /// lines drawn with pens in the mask.
+    pub fn fill_poly_masked(&mut self, poly: &Polygon, mask: &PenMask) {
+        let mut drop_segments: Vec<SceneLine> = Vec::new();
+        let mut new_segments: Vec<SceneLine> = Vec::new();
 
-            if !inter && (!pre || !post) {
-                // If the line does not have any intersections, we do not need to remove it.
-                // This is not necessary for correctness (since the segment would be removed
-                // and added back), but it's a big performance improvement.
+        for entry in self.lines.locate_in_envelope_intersecting(&poly.envelope()) {
+            if !mask.contains(entry.line.pen) {
                 continue;
             }
-
-            drop_segments.push(*line);
-
-            crossings.sort_by(|a, b| a.partial_cmp(b).unwrap());
-
-            let mut draw = true;
-            let mut last = 0.;
-            let v = line.vector();
-            for frac in crossings {
-                if frac > 1. {
-                    break;
-                } else if frac > last {
-                    if draw {
-                        new_segments.push(LineSegment::new_with_pen(
-                            line.c1 + v * last,
-                            line.c1 + v * frac,
-                            line.pen,
-                        ));
-                    }
-                    last = frac;
+            match clip_segment(&entry.line, poly) {
+                Clip::Outside => (),
+                Clip::Inside => drop_segments.push(*entry),
+                Clip::Split { outside, .. } => {
+                    drop_segments.push(*entry);
+                    new_segments
+                        .extend(outside.into_iter().map(|line| SceneLine { line, ..*entry }));
                 }
-
-                draw = !draw;
-            }
-
-            if draw && last < 1. {
-                new_segments.push(LineSegment::new_with_pen(
-                    line.c1 + v * last,
-                    line.c2,
-                    line.pen,
-                ));
             }
         }
 
@@ -153,44 +238,142 @@ impl Scene {
             self.lines.remove(&seg);
         }
 
-        for line in new_segments {
-            self.add_segment(line);
+        for seg in new_segments {
+            self.lines.insert(seg);
         }
     }
 
-    /// Draw lines around the perimeter of a polygon and its holes.
-    pub fn stroke_poly(&mut self, poly: &Polygon, pen: usize) {
-        for line in poly.points.line_segments_with_pen(pen) {
-            self.add_segment(line);
-        }
-        for hole in poly.holes.iter() {
-            for line in hole.line_segments_with_pen(pen) {
-                self.add_segment(line);
+    /// Keep only the parts of line segments inside the given polygon, erasing
+    /// everything else. This is the reverse of `fill_poly`, and like it, only
+    /// affects lines that are already in the scene.
+    pub fn clip_to(&mut self, poly: &Polygon) {
+        let envelope = poly.envelope();
+        let mut kept: Vec<SceneLine> = Vec::new();
+
+        for entry in &self.lines {
+            if !envelope.intersects(&entry.envelope()) {
+                continue;
+            }
+            match clip_segment(&entry.line, poly) {
+                Clip::Outside => (),
+                Clip::Inside => kept.push(*entry),
+                Clip::Split { inside, .. } => {
+                    kept.extend(inside.into_iter().map(|line| SceneLine { line, ..*entry }));
+                }
             }
         }
+
+        self.lines = RTree::bulk_load(kept);
+    }
+
+    /// Keep only the parts of line segments inside the rectangle with the
+    /// given corners, and use the rectangle as the bounds of the scene so
+    /// that exports are framed to it rather than fitted to the lines.
+    pub fn crop(&mut self, corner1: Point, corner2: Point) {
+        let frame = AABB::from_corners([corner1.x, corner1.y], [corner2.x, corner2.y]);
+        let (lower, upper) = (frame.lower(), frame.upper());
+        self.clip_to(&Polygon::from_coords(vec![
+            (lower[0], lower[1]),
+            (upper[0], lower[1]),
+            (upper[0], upper[1]),
+            (lower[0], upper[1]),
+        ]));
+        self.frame = Some(frame);
+    }
+
+    /// Cover the inside of a polygon with hatch lines drawn in the given pen.
+    /// Like `stroke_poly`, this should be done *after* `fill_poly` so that the
+    /// polygon does not erase its own hatching. The lines count as hatching,
+    /// so `set_hatch_pen` changes their pen.
+    pub fn hatch_poly(&mut self, poly: &Polygon, hatch: &Hatch, pen: usize) -> ObjectId {
+        let object = self.new_object();
+        self.add_lines(hatch.lines(poly, pen), object, true);
+        object
+    }
+
+    /// Draw lines around the perimeter of a polygon and its holes.
+    pub fn stroke_poly(&mut self, poly: &Polygon, pen: usize) -> ObjectId {
+        let object = self.new_object();
+        self.add_lines(stroke_lines(poly, pen), object, false);
+        object
+    }
+
+    /// Adds a polygon without holes to the scene with a given draw mode, but
+    /// only strokes the edges (from point `i` to point `i + 1`) that are
+    /// flagged in `edges`.
+    pub(crate) fn add_poly_with_edges(
+        &mut self,
+        poly: &Polygon,
+        draw_mode: DrawMode,
+        edges: &[bool],
+    ) -> ObjectId {
+        self.add_poly_with_stroke(poly, draw_mode, |pen| {
+            let points = &poly.points.0;
+            edges
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| **e)
+                .map(|(i, _)| {
+                    LineSegment::new_with_pen(points[i], points[(i + 1) % points.len()], pen)
+                })
+                .collect()
+        })
     }
 
     /// Adds a polygon to the scene with the default draw mode.
-    pub fn add_poly(&mut self, poly: &Polygon) {
-        self.fill_poly(&poly);
-        self.stroke_poly(poly, 0);
+    pub fn add_poly(&mut self, poly: &Polygon) -> ObjectId {
+        self.add_poly_with_draw_mode(poly, DrawMode::default())
+    }
+
+    /// Adds a polygon to the scene with a given draw mode. Its outline and
+    /// hatching make up a single object.
+    pub fn add_poly_with_draw_mode(&mut self, poly: &Polygon, draw_mode: DrawMode) -> ObjectId {
+        self.add_poly_with_stroke(poly, draw_mode, |pen| stroke_lines(poly, pen))
     }
 
-    /// Adds a polygon to the scene with a given draw mode.
-    pub fn add_poly_with_draw_mode(&mut self, poly: &Polygon, draw_mode: DrawMode) {
+    /// Fill and hatch a polygon as a new object according to the draw mode,
+    /// then, if the draw mode has a pen, add the outline that `stroke` gives
+    /// for that pen.
+    fn add_poly_with_stroke(
+        &mut self,
+        poly: &Polygon,
+        draw_mode: DrawMode,
+        stroke: impl FnOnce(usize) -> Vec<LineSegment>,
+    ) -> ObjectId {
+        let object = self.new_object();
         if draw_mode.fill {
-            self.fill_poly(&poly)
+            self.fill_poly_masked(poly, &draw_mode.occludes)
+        }
+        if let Some((hatch, pen)) = &draw_mode.hatch {
+            self.add_lines(hatch.lines(poly, *pen), object, true)
         }
         if let Some(pen) = draw_mode.pen {
-            self.stroke_poly(&poly, pen)
+            self.add_lines(stroke(pen), object, false)
         }
+        object
+    }
+}
+
+/// The segments around the perimeter of a polygon and its holes.
+fn stroke_lines(poly: &Polygon, pen: usize) -> Vec<LineSegment> {
+    std::iter::once(&poly.points)
+        .chain(&poly.holes)
+        .flat_map(|l| l.line_segments_with_pen(pen))
+        .collect()
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Scene::new()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::pt;
+    use crate::draw_mode::{fill_only, pen, stroke};
+    use crate::types::{pt, vec, PointActions};
+    use proptest::prelude::*;
 
     #[test]
     fn test_double_intersection() {
@@ -203,12 +386,11 @@ mod tests {
 
         sc.fill_poly(&poly);
 
-        let result: Vec<&LineSegment> = sc.lines.iter().collect();
+        let result: Vec<&LineSegment> = sc.lines.iter().map(|l| &l.line).collect();
 
         assert_eq!(vec![&LineSegment::new(pt(0., 0.), pt(5., 0.))], result);
     }
 
-    /*
     #[test]
     fn test_double_cut() {
         let mut sc = Scene::new();
@@ -220,7 +402,7 @@ mod tests {
 
         sc.fill_poly(&poly);
 
-        let mut result: Vec<&LineSegment> = sc.lines.iter().collect();
+        let mut result: Vec<&LineSegment> = sc.lines.iter().map(|l| &l.line).collect();
         result.sort();
 
         assert_eq!(
@@ -253,7 +435,7 @@ mod tests {
 
         let poly = Polygon::new(vec![pt(3., 1.), pt(6., 7.), pt(0., 7.)]);
         sc.fill_poly(&poly);
-        let mut result: Vec<&LineSegment> = sc.lines.iter().collect();
+        let mut result: Vec<&LineSegment> = sc.lines.iter().map(|l| &l.line).collect();
         result.sort();
 
         let mut expect = vec![
@@ -267,7 +449,6 @@ mod tests {
 
         assert_eq!(expect, result)
     }
-    */
 
     #[test]
     fn test_polygon_hole() {
@@ -281,9 +462,233 @@ mod tests {
         );
         sc.fill_poly(&poly);
 
-        let result: Vec<&LineSegment> = sc.lines.iter().collect();
+        let result: Vec<&LineSegment> = sc.lines.iter().map(|l| &l.line).collect();
 
         let expected = LineSegment::new(pt(5., 7.), pt(10., 7.));
         assert_eq!(vec![&expected], result)
     }
+
+    #[test]
+    fn test_masked_fill() {
+        let mut sc = Scene::new();
+        for pen in 0..3 {
+            let y = pen as f64;
+            sc.add_segment(LineSegment::new_with_pen(pt(0., y), pt(10., y), pen));
+        }
+
+        // A red overlay that hides black lines but not blue or red ones.
+        let overlay = Polygon::from_coords(vec![(4., -1.), (6., -1.), (6., 3.), (4., 3.)]);
+        sc.add_poly_with_draw_mode(&overlay, fill_only().occluding(PenMask::Only(vec![0])));
+
+        let mut result: Vec<&LineSegment> = sc.lines.iter().map(|l| &l.line).collect();
+        result.sort();
+        assert_eq!(
+            vec![
+                &LineSegment::new_with_pen(pt(0., 0.), pt(4., 0.), 0),
+                &LineSegment::new_with_pen(pt(0., 1.), pt(10., 1.), 1),
+                &LineSegment::new_with_pen(pt(0., 2.), pt(10., 2.), 2),
+                &LineSegment::new_with_pen(pt(6., 0.), pt(10., 0.), 0),
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn test_polyline_order() {
+        let mut sc = Scene::new();
+        let zigzag = Polyline::new((0..10).map(|i| pt(i as f64, (i % 2) as f64)).collect());
+        sc.add_polyline(&zigzag, 1);
+        sc.fill_poly(&Polygon::from_coords(vec![
+            (3.5, -1.),
+            (4.5, -1.),
+            (4.5, 2.),
+            (3.5, 2.),
+        ]));
+
+        // The segments crossing the fill are cut, and the rest come out in
+        // the order of the polyline.
+        let lines = sc.to_plot().unwrap().layers[0].lines.clone();
+        assert_eq!(9, lines.len());
+        assert!(lines.iter().all(|l| l.pen == 1));
+        assert_eq!(pt(0., 0.), lines[0].c1);
+        for pair in lines.windows(2) {
+            assert!(pair[0].c1.x < pair[1].c1.x);
+        }
+        assert!(lines
+            .iter()
+            .all(|l| l.c1.x.max(l.c2.x) <= 3.5 || l.c1.x >= 4.5));
+    }
+
+    #[test]
+    fn test_split_order() {
+        // Fills that cut a long segment into pieces of different lengths.
+        let mut sc = Scene::new();
+        sc.add_segment(LineSegment::new(pt(0., 0.), pt(10., 0.)));
+        for (a, b) in &[(1., 2.), (3., 4.), (9., 9.5)] {
+            sc.fill_poly(&Polygon::from_coords(vec![
+                (*a, -1.),
+                (*b, -1.),
+                (*b, 1.),
+                (*a, 1.),
+            ]));
+        }
+
+        let starts: Vec<f64> = sc.to_plot().unwrap().layers[0]
+            .lines
+            .iter()
+            .map(|l| l.c1.x)
+            .collect();
+        assert_eq!(vec![0., 2., 4., 9.5], starts);
+    }
+
+    #[test]
+    fn test_remove_and_set_pen() {
+        let mut sc = Scene::new();
+        let back = sc.add_poly_with_draw_mode(
+            &Polygon::from_coords(vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.)]),
+            stroke(0),
+        );
+        let front = sc.add_poly_with_draw_mode(
+            &Polygon::from_coords(vec![(2., 2.), (6., 2.), (6., 6.), (2., 6.)]),
+            pen(0).hatch(
+                Hatch::Parallel {
+                    angle: 0.,
+                    spacing: 1.,
+                },
+                1,
+            ),
+        );
+        assert_ne!(back, front);
+        let count =
+            |sc: &Scene, pen: usize| sc.segments(front).iter().filter(|l| l.pen == pen).count();
+        assert_eq!(4, count(&sc, 0));
+        assert_eq!(4, count(&sc, 1));
+        // The front square erased the corner of the back one.
+        assert_eq!(4, sc.segments(back).len());
+
+        // The outline and the hatching are restyled separately.
+        sc.set_pen(front, 2);
+        assert_eq!((4, 4), (count(&sc, 2), count(&sc, 1)));
+        sc.set_hatch_pen(front, 3);
+        assert_eq!((4, 4), (count(&sc, 2), count(&sc, 3)));
+        assert!(sc.segments(back).iter().all(|l| l.pen == 0));
+
+        sc.remove(front);
+        assert!(sc.segments(front).is_empty());
+        let lines = sc.to_plot().unwrap().layers[0].lines.clone();
+        assert_eq!(4, lines.len());
+        assert!(lines.iter().all(|l| l.object == Some(back)));
+    }
+
+    #[test]
+    fn test_empty_scene() {
+        let mut sc = Scene::new();
+        assert!(sc.bounds().is_none());
+        assert!(matches!(sc.to_plot(), Err(Error::EmptyScene)));
+
+        // A cropped scene is a blank page of the crop size.
+        sc.crop(pt(0., 0.), pt(10., 5.));
+        let plot = sc.to_plot().unwrap();
+        assert!(plot.layers.is_empty());
+        assert_eq!(pt(10., 5.), plot.upper_bound);
+    }
+
+    #[test]
+    fn test_crop() {
+        let mut sc = Scene::new();
+        sc.add_segment(LineSegment::new(pt(-5., 1.), pt(5., 1.)));
+        sc.add_segment(LineSegment::new(pt(1., 1.), pt(2., 2.)));
+        sc.add_segment(LineSegment::new(pt(20., 20.), pt(30., 20.)));
+
+        sc.crop(pt(4., 4.), pt(0., 0.));
+        let mut result: Vec<&LineSegment> = sc.lines.iter().map(|l| &l.line).collect();
+        result.sort();
+        assert_eq!(
+            vec![
+                &LineSegment::new(pt(0., 1.), pt(4., 1.)),
+                &LineSegment::new(pt(1., 1.), pt(2., 2.)),
+            ],
+            result
+        );
+
+        let plot = sc.to_plot().unwrap();
+        assert_eq!(pt(0., 0.), plot.lower_bound);
+        assert_eq!(pt(4., 4.), plot.upper_bound);
+
+        // Clipping to a triangle keeps only the part below its diagonal.
+        sc.clip_to(&Polygon::new(vec![pt(0., 0.), pt(4., 0.), pt(4., 2.)]));
+        let result: Vec<&LineSegment> = sc.lines.iter().map(|l| &l.line).collect();
+        assert_eq!(vec![&LineSegment::new(pt(2., 1.), pt(4., 1.))], result);
+    }
+
+    /// Coordinates on a small integer grid, so that collinear points and
+    /// segments through vertices are common, or arbitrary coordinates.
+    fn coords() -> impl Strategy<Value = (f64, f64)> {
+        prop_oneof![
+            (0..8i32, 0..8i32).prop_map(|(x, y)| (x as f64, y as f64)),
+            (-10.0..10.0f64, -10.0..10.0f64),
+        ]
+    }
+
+    fn segments() -> impl Strategy<Value = Vec<LineSegment>> {
+        prop::collection::vec((coords(), coords()), 1..5).prop_map(|ends| {
+            ends.into_iter()
+                .map(|((x1, y1), (x2, y2))| LineSegment::new(pt(x1, y1), pt(x2, y2)))
+                .collect()
+        })
+    }
+
+    fn polygons() -> impl Strategy<Value = Polygon> {
+        prop::collection::vec(coords(), 3..7).prop_map(Polygon::from_coords)
+    }
+
+    /// Move the polygon by a small but finite amount in the direction
+    /// that `fill_poly` resolves degenerate cases, so that points away from
+    /// the boundary can be classified without exact arithmetic.
+    fn perturbed(poly: &Polygon) -> Polygon {
+        poly.clone().translate(vec(-1e-6, -1e-12))
+    }
+
+    proptest! {
+        #[test]
+        fn prop_fill_removes_covered_lines(lines in segments(), poly in polygons()) {
+            let mut sc = Scene::new();
+            for line in &lines {
+                sc.add_segment(*line);
+            }
+            sc.fill_poly(&poly);
+
+            let reference = perturbed(&poly);
+            for SceneLine { line, .. } in &sc.lines {
+                if line.vector().norm() > 1e-3 {
+                    let midpoint = line.c1 + line.vector() / 2.;
+                    prop_assert!(!reference.points.contains(&midpoint));
+                }
+            }
+        }
+
+        #[test]
+        fn prop_fill_keeps_uncovered_lines(lines in segments(), poly in polygons()) {
+            let mut sc = Scene::new();
+            for line in &lines {
+                sc.add_segment(*line);
+            }
+            sc.fill_poly(&poly);
+
+            let reference = perturbed(&poly);
+            for line in &lines {
+                for i in 0..20 {
+                    let p = line.c1 + line.vector() * ((i as f64 + 0.5) / 20.);
+                    let near_boundary = reference
+                        .points
+                        .line_segments()
+                        .iter()
+                        .any(|edge| edge.distance_to(&p) < 1e-3);
+                    if !near_boundary && !reference.points.contains(&p) {
+                        prop_assert!(sc.lines.iter().any(|l| l.line.distance_to(&p) < 1e-9));
+                    }
+                }
+            }
+        }
+    }
 }
@@ -1,8 +1,10 @@
 //! Aliases and extensions for 2D points and vectors.
 
+pub mod object_id;
 pub mod point;
 pub mod to_string;
 pub mod vector;
 
+pub use object_id::ObjectId;
 pub use point::{pt, Point, PointActions, PointContainer};
 pub use vector::{vec, Vector, VectorExtension};
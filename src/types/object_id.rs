@@ -0,0 +1,11 @@
+//! Identifiers for the objects added to a `Scene`.
+
+/// Identifies an object (a segment, polyline or polygon) added to a scene.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ObjectId(pub(crate) usize);
+
+impl std::fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}